@@ -0,0 +1,56 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use reddit_api_scraper::api_scraper;
+use reddit_api_scraper::config::Scraping;
+use reddit_api_scraper::verbosity;
+
+const SNAPSHOT: &str = include_str!("../fixtures/dev_api.html");
+const LEGACY_SNAPSHOT: &str = include_str!("../fixtures/dev_api_legacy.html");
+
+/*
+ * The live page documents a few hundred endpoints, far more than the fixture, so this copies each of the fixture's
+ * sections, with their endpoints' anchors changed (e.g. GET_copy1_api_v1_me), to get closer to that. Looking up every
+ * endpoint by its anchor is what gets slow as the page grows.
+ */
+fn with_copies_of_sections(html: &str, copies: usize) -> String {
+  let toc_start = html.find("<li><a href=\"#section_").unwrap();
+  let toc_end = html.find("        </ul>\n      </li>\n    </ul>").unwrap();
+  let sections_start = html.find("<div class=\"section\" id=\"section_").unwrap();
+  let sections_end = html.rfind("\n</div>\n</body>").unwrap();
+
+  let mut toc = html[toc_start..toc_end].to_string();
+  let mut sections = html[sections_start..sections_end].to_string();
+  for copy in 1..=copies {
+    let rename = |part: &str| {
+      let mut part = part.replace("section_", &format!("section_copy{}_", copy));
+      for verb in ["GET", "POST", "PATCH"] {
+        part = part
+          .replace(&format!("#{}_", verb), &format!("#{}_copy{}_", verb, copy))
+          .replace(&format!("id=\"{}_", verb), &format!("id=\"{}_copy{}_", verb, copy));
+      }
+      part
+    };
+    toc.push_str(&rename(&html[toc_start..toc_end]));
+    sections.push_str(&rename(&html[sections_start..sections_end]));
+  }
+
+  html[..toc_start].to_string() + &toc + &html[toc_end..sections_start] + &sections + &html[sections_end..]
+}
+
+fn scrape(c: &mut Criterion) {
+  verbosity::set(verbosity::QUIET);
+  let scraping = Scraping::default();
+  let large_snapshot = with_copies_of_sections(SNAPSHOT, 100);
+
+  c.bench_function("scrape fixtures/dev_api.html", |b| {
+    b.iter(|| api_scraper::scrape_catalog(SNAPSHOT, None, &scraping).unwrap())
+  });
+  c.bench_function("scrape fixtures/dev_api_legacy.html", |b| {
+    b.iter(|| api_scraper::scrape_catalog(LEGACY_SNAPSHOT, None, &scraping).unwrap())
+  });
+  c.bench_function("scrape fixtures/dev_api.html with 100 copies of its sections", |b| {
+    b.iter(|| api_scraper::scrape_catalog(&large_snapshot, None, &scraping).unwrap())
+  });
+}
+
+criterion_group!(benches, scrape);
+criterion_main!(benches);
@@ -1,209 +1,260 @@
-use crate::generator;
+use crate::catalog::{ApiCatalog, ApiSection, Endpoint, Location, Parameter, ParameterType, Scope};
+use crate::config;
+use crate::document_index::DocumentIndex;
 use crate::http_verb::HttpVerb;
+use crate::layout;
+use crate::layout::Layout;
+use crate::scrape_error::ScrapeError;
 use crate::template_uri;
+use crate::template_uri::UriPrototype;
 
-use regex::Regex;
-use scraper::element_ref::ElementRef;
-use scraper::{Html, Selector};
-use std::collections::HashMap;
+use scraper::Html;
 
-const API_SECTION_CONTAINER_SELECTOR_STRING: &str = "div.toc > ul > li > ul > li";
-const API_SECTION_API_SELECTOR_STRING: &str = concat!("div.toc > ul > li > ul > li", " > ul > li > a");
-
-pub async fn scrape(html: &str) -> Result<(), Box<dyn std::error::Error>> {
+/*
+ * Scrapes the docs with the named layout, or whichever fits the document if there's no name. Nothing is scraped unless
+ * the document has all the landmarks the layout relies on.
+ */
+pub fn scrape_catalog(html: &str, layout: Option<&str>, scraping: &config::Scraping) -> Result<ApiCatalog, ScrapeError> {
   let document = Html::parse_document(html);
-
-  let div_sidebar_selector = Selector::parse("div.content div.sidebar").unwrap();
-  let div_sidebar = document.select(&div_sidebar_selector).next().unwrap();
-
-  let api_section_container_selector = Selector::parse(API_SECTION_CONTAINER_SELECTOR_STRING).unwrap();
-  let api_section_container = div_sidebar.select(&api_section_container_selector);
-
-  println!("Number of elements found: {}", api_section_container.clone().count());
-
-  let api_section_header_selector = Selector::parse("a").unwrap();
-
-  for (i, element) in api_section_container.enumerate() {
-    let api_section_header = element
-      .select(&api_section_header_selector)
-      .next()
-      .unwrap()
-      .text()
-      .collect::<Vec<_>>()[0];
-
-    println!("Section {}: {}", i, api_section_header);
-
-    let filename = str::replace(api_section_header, "&", "and");
-    let filename = str::replace(&filename, " ", "_");
-
-    generator::generate().await?;
-    let execution_file = generator::create_execution_file(&filename).await?;
-    let wrapper_file = generator::create_wrapper_file(&filename).await?;
-    let request_model_file = generator::create_request_model_file(&filename).await?;
-
-    let api_section_api_selector = Selector::parse(API_SECTION_API_SELECTOR_STRING).unwrap();
-
-    for (_, api_section) in element.select(&api_section_api_selector).enumerate() {
-      let api_section_element = api_section.value();
-
-      // E.g. #GET_wiki_{page}
-      let href_to_api = api_section_element.attr("href").unwrap();
-
-      let escaped_href_to_api = escape_special_characters(href_to_api);
-
-      // println!("API is: {} and Escaped href: {}", api, escaped_href_to_api);
-      let api_details_selector = Selector::parse(&escaped_href_to_api).unwrap();
-      let api_details_selected = document.select(&api_details_selector);
-
-      let http_verb = word_before_underscore(href_to_api).trim_start_matches('#');
-      let http_verb = HttpVerb::from(http_verb);
-
-      let uris_as_strings = get_uri_from_api_details(api_details_selected, &http_verb);
-
-      for uri in uris_as_strings {
-        match http_verb {
-          HttpVerb::GET => {
-            generator::write_get_api(&uri, &execution_file)?;
-            generator::write_get_wrapper(&uri, &api_section_header, &wrapper_file)?;
-            generator::write_request_model_file(&uri, &request_model_file)?;
-          }
-          HttpVerb::POST => {
-            generator::write_post_api(&http_verb, &uri, &execution_file)?;
-            generator::write_wrapper(&http_verb, &uri, &filename, &wrapper_file)?;
-            generator::write_request_model_file(&uri, &request_model_file)?;
-          }
-          _ => {
-            println!("        Support for {} not yet implemented", http_verb);
-          }
+  let index = DocumentIndex::new(&document);
+
+  let layout = layout_of(&index, layout, scraping)?;
+  let layout = layout.as_ref();
+  info!("Using the {} layout", layout.name());
+
+  let api_section_container = layout.sections(&index);
+  info!("Number of elements found: {}", api_section_container.len());
+
+  let mut sections = Vec::new();
+  let mut failures = Vec::new();
+  for (i, element) in api_section_container.into_iter().enumerate() {
+    let element = match element {
+      Ok(element) => element,
+      Err(error) => {
+        failures.push(error);
+        continue;
+      }
+    };
+    info!("Section {}: {}", i, element.name);
+
+    let mut endpoints = Vec::new();
+    for api_section in element.endpoints {
+      let api_section = match api_section {
+        Ok(api_section) => api_section,
+        Err(error) => {
+          failures.push(error);
+          continue;
         }
+      };
+      debug!("    {}", api_section.anchor);
+
+      match scrape_endpoint(layout, &element.name, api_section, &mut failures) {
+        Ok(endpoint) => endpoints.push(endpoint),
+        Err(error) => failures.push(error),
       }
     }
-  }
 
-  Ok(())
-}
-
-fn get_uri_from_api_details(
-  api_details: scraper::html::Select,
-  http_verb: &HttpVerb,
-) -> Vec<template_uri::TemplateUri> {
-  let uri_variants_selector = Selector::parse(".uri-variants li").unwrap();
-
-  let api_detail = api_details.enumerate().next();
-
-  if api_detail == None {
-    return Vec::new();
+    sections.push(ApiSection {
+      name: element.name,
+      endpoints,
+    });
   }
 
-  // There should be only one, assume it's that way for now
-  let api_detail = api_detail.unwrap().1;
-
-  let uri_variants = api_detail.select(&uri_variants_selector);
-  let mut num_variants = 0;
+  Ok(ApiCatalog {
+    overview: layout.overview(&index),
+    sections,
+    scopes: Vec::new(),
+    failures,
+  })
+}
 
-  let request_fields = match http_verb {
-    HttpVerb::POST => get_request_body_from_api_details(api_detail),
-    HttpVerb::GET => get_request_body_from_api_details(api_detail),
-    _ => HashMap::new(),
+/*
+ * An endpoint that can't be scraped is left out entirely, while a URI that can't be parsed or a parameter row that
+ * can't be read only leaves out that URI or row; either way the failure is kept.
+ */
+fn scrape_endpoint(
+  layout: &dyn Layout,
+  section: &str,
+  api_section: layout::SidebarEndpoint,
+  failures: &mut Vec<ScrapeError>,
+) -> Result<Endpoint, ScrapeError> {
+  let anchor = api_section.anchor;
+  let verb = word_before_underscore(&anchor);
+  let http_verb = verb.parse::<HttpVerb>().map_err(|_| ScrapeError::UnknownVerb {
+    section: section.to_string(),
+    anchor: anchor.clone(),
+    verb: verb.to_string(),
+  })?;
+
+  let (uris, parameters, description, scopes, listing) = match api_section.details {
+    Some(api_detail) => {
+      let mut parameter_failures = Vec::new();
+      let parameters = layout.parameters(api_detail, &mut parameter_failures);
+      failures.extend(parameter_failures.into_iter().map(|error| error.at(section, &anchor)));
+      let mut uris = Vec::new();
+      for prototype in layout.uri_prototypes(api_detail) {
+        match uri_prototype_into_concrete(&prototype) {
+          Ok(concrete) => uris.extend(concrete),
+          Err(reason) => failures.push(ScrapeError::MalformedUri {
+            section: section.to_string(),
+            anchor: anchor.clone(),
+            uri: prototype,
+            reason,
+          }),
+        }
+      }
+      let parameters = parameters
+        .into_iter()
+        .map(|(name, description)| Parameter {
+          location: location_of(&name, &http_verb, &uris),
+          parameter_type: type_of(&description),
+          name,
+          description,
+        })
+        .collect();
+      (
+        uris,
+        parameters,
+        layout.description(api_detail),
+        layout.scopes(api_detail),
+        layout.is_listing(api_detail),
+      )
+    }
+    None => (Vec::new(), Vec::new(), String::new(), Vec::new(), false),
   };
 
-  let mut variants: Vec<template_uri::TemplateUri> = Vec::new();
-  for variant in uri_variants {
-    num_variants += 1;
-    variants.extend(uri_prototype_into_concrete(
-      &collect_children_as_string(variant)
-        .unwrap()
-        .trim_start_matches('→')
-        .trim()
-        .to_string(),
-      request_fields.clone(),
-    ));
-  }
+  Ok(Endpoint {
+    anchor,
+    verb: http_verb,
+    uris,
+    parameters,
+    description,
+    scopes,
+    listing,
+  })
+}
 
-  if num_variants > 0 {
-    return variants;
-  }
+/*
+ * The OAuth scope pages are laid out like the rest of the docs, but with a section per scope: the scope's description
+ * under its heading, then the endpoints it gives access to. A page can document one scope or all of them. Entries that
+ * can't be scraped are only reported, a scope's endpoints are just for cross-checking.
+ */
+pub fn scrape_scopes(html: &str, layout: Option<&str>, scraping: &config::Scraping) -> Result<Vec<Scope>, ScrapeError> {
+  let document = Html::parse_document(html);
+  let index = DocumentIndex::new(&document);
+
+  let layout = layout_of(&index, layout, scraping)?;
+  debug!("Using the {} layout for the scopes", layout.name());
+
+  let mut scopes = Vec::new();
+  for section in layout.sections(&index) {
+    let section = match section {
+      Ok(section) => section,
+      Err(error) => {
+        info!("warning: {}", error);
+        continue;
+      }
+    };
+    debug!("Scope {}", section.name);
 
-  return match get_api_from_api_details(api_detail) {
-    Some(api) => uri_prototype_into_concrete(&api, request_fields.clone()),
-    None => Vec::new(),
-  };
-}
+    let mut endpoints = Vec::new();
+    for endpoint in section.endpoints {
+      match endpoint {
+        Ok(endpoint) => endpoints.push(endpoint.anchor),
+        Err(error) => info!("warning: {}", error),
+      }
+    }
 
-fn get_request_body_from_api_details(api_detail: ElementRef) -> HashMap<String, String> {
-  let parameter_row_selector = Selector::parse("table.parameters > tbody > tr").unwrap();
-  let parameter_description_selector = Selector::parse("td > p").unwrap();
-  let parameter_name_selector = Selector::parse("th").unwrap();
-  let parameter_row_selection = api_detail.select(&parameter_row_selector);
-
-  let mut request_fields = HashMap::new();
-  for selection in parameter_row_selection {
-    let parameter_name = selection.select(&parameter_name_selector).next().unwrap();
-    let parameter_description = selection.select(&parameter_description_selector).next();
-    request_fields.insert(
-      parameter_name.inner_html(),
-      match parameter_description {
-        Some(description) => description.inner_html(),
-        None => "".to_string(),
+    scopes.push(Scope {
+      name: section.name,
+      description: match section.details {
+        Some(details) => layout.section_description(details),
+        None => String::new(),
       },
-    );
+      endpoints,
+    });
   }
 
-  return request_fields;
+  Ok(scopes)
 }
 
-fn get_api_from_api_details(api_detail: ElementRef) -> Option<String> {
-  let h3_selector = Selector::parse("h3").unwrap();
-  let h3_selection = api_detail.select(&h3_selector);
-
-  for h3 in h3_selection {
-    // Assuming there's only one...
-    return collect_children_as_string(h3);
-  }
-
-  None
+// Each step of scraping one endpoint, for explaining where what's generated for it came from
+pub struct Trace {
+  pub section: String,
+  // The element documenting the endpoint, as it is in the docs, if the anchor leads anywhere
+  pub html: Option<String>,
+  // Each URI as documented, and the URIs it expands into
+  pub uris: Vec<(String, Result<Vec<template_uri::TemplateUri>, String>)>,
+  pub endpoint: Result<Endpoint, ScrapeError>,
 }
 
-fn collect_children_as_string(parent: ElementRef) -> Option<String> {
-  let mut uri_parts: Vec<String> = Vec::new();
-  for child in parent.children() {
-    match (*child.value()).as_element() {
-      Some(element) => {
-        if element.name() == "span" || element.name() == "a" {
-          continue;
-        }
+// Scrapes only the endpoint with the anchor, e.g. GET_wiki_{page}, if the docs have it
+pub fn trace_endpoint(
+  html: &str,
+  anchor: &str,
+  layout: Option<&str>,
+  scraping: &config::Scraping,
+) -> Result<Option<Trace>, ScrapeError> {
+  let document = Html::parse_document(html);
+  let index = DocumentIndex::new(&document);
+
+  let layout = layout_of(&index, layout, scraping)?;
+  let layout = layout.as_ref();
+  info!("Using the {} layout", layout.name());
+
+  for section in layout.sections(&index).into_iter().flatten() {
+    let endpoint = section
+      .endpoints
+      .into_iter()
+      .flatten()
+      .find(|endpoint| endpoint.anchor == anchor);
+    let endpoint = match endpoint {
+      Some(endpoint) => endpoint,
+      None => continue,
+    };
 
-        let element_ref = ElementRef::wrap(child).unwrap();
-        if element.name() == "em" {
-          uri_parts.push("{{".to_string() + &element_ref.inner_html().to_string() + "}}");
-        } else {
-          uri_parts.push(element_ref.inner_html());
-        }
-      }
-      _ => {
-        uri_parts.push((*child.value()).as_text().unwrap().text.to_string());
+    let mut uris = Vec::new();
+    if let Some(details) = endpoint.details {
+      for prototype in layout.uri_prototypes(details) {
+        let concrete = uri_prototype_into_concrete(&prototype);
+        uris.push((prototype, concrete));
       }
     }
-  }
 
-  let mut uri_as_string = String::new();
-  for uri_part in uri_parts {
-    uri_as_string.push_str(&uri_part);
+    // Any malformed URIs are already in the trace
+    let mut failures = Vec::new();
+    return Ok(Some(Trace {
+      html: endpoint.details.map(|details| details.html()),
+      uris,
+      endpoint: scrape_endpoint(layout, &section.name, endpoint, &mut failures),
+      section: section.name,
+    }));
   }
 
-  if uri_as_string.is_empty() {
-    None
-  } else {
-    Some(uri_as_string)
-  }
+  Ok(None)
 }
 
-/*
- * Some of the characters in the HTML element IDs are special characters in CSS selectors. Escape those special
- * characters so that the CSS selector will actually work instead of blowing up.
- */
-fn escape_special_characters(string: &str) -> String {
-  string.replace("{", "\\{").replace("}", "\\}").replace(":", "\\:")
+fn layout_of(
+  index: &DocumentIndex,
+  name: Option<&str>,
+  scraping: &config::Scraping,
+) -> Result<Box<dyn Layout>, ScrapeError> {
+  let layout = match name {
+    Some(name) => layout::by_name(name, &scraping.selectors)?.ok_or(ScrapeError::UnknownLayout)?,
+    None => match layout::detect(index.document(), &scraping.selectors)? {
+      Some(layout) => layout,
+      None => {
+        // Saying what the live page's layout is missing is more use than just not recognizing the document
+        if let Some(current) = layout::by_name(layout::LAYOUT_NAMES[0], &scraping.selectors)? {
+          layout::preflight(current.as_ref(), index, scraping.min_endpoints)?;
+        }
+        return Err(ScrapeError::UnknownLayout);
+      }
+    },
+  };
+
+  layout::preflight(layout.as_ref(), index, scraping.min_endpoints)?;
+  Ok(layout)
 }
 
 fn word_before_underscore(s: &str) -> &str {
@@ -215,59 +266,183 @@ fn word_before_underscore(s: &str) -> &str {
     }
   }
 
-  &s[..]
+  s
 }
 
 /*
  * A bunch of APIs are of the form [/r/subreddit]/about/banned where the API works as written but
  * also without the /r/subreddit prefix, so there are actually two APIS ('/about/banned' and
- * '/r/subreddit/about/banned')
+ * '/r/subreddit/about/banned'). Some have more than one optional part, each of which doubles the number of APIs.
  */
-fn uri_prototype_into_concrete(
-  prototype: &str,
-  request_fields: HashMap<String, String>,
-) -> Vec<template_uri::TemplateUri> {
-  let uri_variant_section = Regex::new(r"\[(.*)\]").unwrap();
-  let uri_parameter = Regex::new(r"(\{\{(\w+)\}\})").unwrap();
-
-  if uri_variant_section.is_match(prototype) {
-    let uri_without_section = uri_variant_section.replace_all(prototype, "").to_string();
-
-    let mut uri_without_section_parameters = HashMap::new();
-    for parameter_match in uri_parameter.captures_iter(&uri_without_section) {
-      uri_without_section_parameters.insert(parameter_match[1].to_string(), parameter_match[2].to_string());
-    }
+fn uri_prototype_into_concrete(prototype: &str) -> Result<Vec<template_uri::TemplateUri>, String> {
+  let prototype = UriPrototype::parse(prototype)?;
+
+  Ok(
+    prototype
+      .expand()
+      .into_iter()
+      .map(|segments| template_uri::TemplateUri { segments })
+      .collect(),
+  )
+}
 
-    // Assume there's a single section for now ([/r/subreddit])
-    let uri_without_section = template_uri::TemplateUri {
-      template: uri_without_section,
-      parameters: uri_without_section_parameters,
-      request_fields: request_fields.clone(),
-    };
+// In the URI if any of the endpoint's URIs has it, otherwise where the generated code sends the rest of the parameters
+fn location_of(name: &str, verb: &HttpVerb, uris: &[template_uri::TemplateUri]) -> Location {
+  if uris.iter().any(|uri| uri.parameters().contains(&name)) {
+    return Location::Uri;
+  }
 
-    let uri_with_section = uri_variant_section.replace_all(prototype, "$1").to_string();
-    let mut uri_with_section_parameters = HashMap::new();
-    for parameter_match in uri_parameter.captures_iter(&uri_with_section) {
-      uri_with_section_parameters.insert(parameter_match[1].to_string(), parameter_match[2].to_string());
-    }
+  match verb {
+    HttpVerb::GET | HttpVerb::HEAD | HttpVerb::DELETE => Location::Query,
+    _ => Location::Body,
+  }
+}
 
-    let uri_with_section = template_uri::TemplateUri {
-      template: uri_with_section,
-      parameters: uri_with_section_parameters,
-      request_fields: request_fields.clone(),
-    };
+/*
+ * The docs describe parameters in prose, but with a handful of stock phrases: "boolean value", "an integer",
+ * "fullname of a thing", "one of (`link`, `self`)". Anything else, including lists and JSON, is left a string.
+ */
+fn type_of(description: &str) -> ParameterType {
+  let description = description.trim_start_matches("(optional)").trim();
+
+  // The values are kept as they are, they're sent as written
+  if let Some(values) = description.strip_prefix("one of (") {
+    let values = values.split(')').next().unwrap_or_default();
+    return ParameterType::OneOf(
+      values
+        .split(',')
+        .map(|value| value.trim().trim_matches('`').to_string())
+        .filter(|value| !value.is_empty())
+        .collect(),
+    );
+  }
 
-    vec![uri_without_section, uri_with_section]
+  let description = description.to_lowercase();
+  if description.starts_with('{') || description.contains("comma-separated") {
+    ParameterType::String
+  } else if description.contains("boolean") {
+    ParameterType::Boolean
+  } else if description.contains("integer") || description.contains("number of") {
+    ParameterType::Integer
+  } else if description.contains("fullname") {
+    ParameterType::Fullname
   } else {
-    let mut parameters = HashMap::new();
-    for parameter_match in uri_parameter.captures_iter(prototype) {
-      parameters.insert(parameter_match[1].to_string(), parameter_match[2].to_string());
-    }
+    ParameterType::String
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
 
-    vec![template_uri::TemplateUri {
-      template: uri_variant_section.replace_all(prototype, "$1").to_string(),
-      parameters,
-      request_fields: request_fields.clone(),
-    }]
+  #[test]
+  fn a_parameter_row_without_a_name_only_leaves_out_that_row() {
+    let html = include_str!("../fixtures/dev_api.html").replace(
+      r#"<tr><th scope="row">return_rtjson</th>"#,
+      "<tr><td>return_rtjson</td>",
+    );
+    let catalog = scrape_catalog(&html, None, &config::Scraping::default()).unwrap();
+
+    let endpoint = catalog
+      .sections
+      .iter()
+      .flat_map(|section| &section.endpoints)
+      .find(|endpoint| endpoint.anchor == "POST_api_comment")
+      .unwrap();
+    let names: Vec<_> = endpoint.parameters.iter().map(|parameter| parameter.name.as_str()).collect();
+    assert_eq!(names, ["api_type", "text", "thing_id", "uh / X-Modhash header"]);
+
+    assert!(catalog.failures.iter().any(|failure| matches!(
+      failure,
+      ScrapeError::MissingElement { anchor, selector, .. } if anchor == "POST_api_comment" && selector.ends_with("> th")
+    )));
+  }
+
+  #[test]
+  fn scrapes_the_legacy_layout() {
+    let html = include_str!("../fixtures/dev_api_legacy.html");
+    let catalog = scrape_catalog(html, None, &config::Scraping::default()).unwrap();
+    assert_eq!(catalog.failures, []);
+
+    let sections: Vec<(&str, Vec<&str>)> = catalog
+      .sections
+      .iter()
+      .map(|section| {
+        let anchors = section.endpoints.iter().map(|endpoint| endpoint.anchor.as_str()).collect();
+        (section.name.as_str(), anchors)
+      })
+      .collect();
+    assert_eq!(
+      sections,
+      [
+        ("account", vec!["GET_api_me.json", "POST_api_login"]),
+        ("links & comments", vec!["POST_api_comment", "POST_api_submit"]),
+        ("listings", vec!["GET_hot"]),
+      ]
+    );
+
+    let (_, login) = catalog.endpoints().find(|(_, endpoint)| endpoint.anchor == "POST_api_login").unwrap();
+    assert_eq!(login.verb, HttpVerb::POST);
+    assert_eq!(login.description, "Log into an account.");
+    let names: Vec<_> = login.parameters.iter().map(|parameter| parameter.name.as_str()).collect();
+    assert_eq!(names, ["api_type", "passwd", "rem", "user"]);
+    assert_eq!(login.parameter("api_type").unwrap().description, "the string `json`");
+
+    let (_, hot) = catalog.endpoints().find(|(_, endpoint)| endpoint.anchor == "GET_hot").unwrap();
+    let uris: Vec<_> = hot.uris.iter().map(|uri| uri.template()).collect();
+    assert_eq!(uris, ["/hot", "/r/{{subreddit}}/hot"]);
+    assert!(hot.listing);
+  }
+
+  #[test]
+  fn scrapes_the_overview() {
+    let html = include_str!("../fixtures/dev_api.html");
+    let overview = scrape_catalog(html, None, &config::Scraping::default()).unwrap().overview;
+
+    let thing_kinds: Vec<_> = overview
+      .thing_kinds
+      .iter()
+      .map(|kind| (kind.prefix.as_str(), kind.name.as_str()))
+      .collect();
+    assert_eq!(
+      thing_kinds,
+      [
+        ("t1_", "Comment"),
+        ("t2_", "Account"),
+        ("t3_", "Link"),
+        ("t4_", "Message"),
+        ("t5_", "Subreddit"),
+        ("t6_", "Award"),
+      ]
+    );
+    // The table is in the thing kinds, not in the prose
+    assert!(overview.fullnames.starts_with("A fullname is a combination of a thing's type (e.g. `Link`)"));
+    assert!(!overview.fullnames.contains("Subreddit"));
+
+    let names: Vec<_> = overview
+      .listing_parameters
+      .iter()
+      .map(|parameter| parameter.name.as_str())
+      .collect();
+    assert_eq!(names, ["after", "before", "limit", "count", "show"]);
+    assert_eq!(
+      overview.listing_parameters[0].description,
+      "only one should be specified. these indicate the [fullname](https://www.reddit.com/dev/api#fullnames) of an \
+       item in the listing to use as the anchor point of the slice."
+    );
+    assert_eq!(
+      overview.listing_parameters[0].description,
+      overview.listing_parameters[1].description
+    );
+    assert!(overview.listings.ends_with("The common parameters are as follows:"));
+
+    assert_eq!(
+      overview.modhashes,
+      "A modhash is a token that the reddit API requires to help prevent \
+       [CSRF](http://en.wikipedia.org/wiki/Cross-site_request_forgery). Modhashes can be obtained via the \
+       [/api/me.json](https://www.reddit.com/dev/api#GET_api_me.json) call or in response data of listing endpoints.\
+       \n\nThe preferred way to send a modhash is to include an `X-Modhash` custom HTTP header with your requests.\
+       \n\nModhashes are not required when authenticated with OAuth."
+    );
   }
 }
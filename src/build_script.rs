@@ -0,0 +1,169 @@
+use crate::generator::{Options, Target};
+use crate::source;
+use crate::source::DocumentSource;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Generated files go in this directory under OUT_DIR, and the file to include! next to it as <this>.rs
+pub const OUT_DIR_NAME: &str = "reddit_api";
+
+/*
+ * Generates the bindings from a build script, for crates that want them generated at compile time from a vendored copy
+ * of the docs rather than checked in:
+ *
+ *   // build.rs
+ *   fn main() {
+ *     let options = reddit_api_scraper::Options::default();
+ *     reddit_api_scraper::build_script::generate(Path::new("docs/dev_api.html"), &options).unwrap();
+ *   }
+ *
+ *   // src/lib.rs
+ *   include!(concat!(env!("OUT_DIR"), "/reddit_api.rs"));
+ *
+ * The snapshot can be a file or a directory of snapshots, but never a URL: builds have to be reproducible and work
+ * offline. options.output_dir is ignored, everything is written under OUT_DIR.
+ */
+pub fn generate(snapshot: &Path, options: &Options) -> Result<PathBuf, Box<dyn std::error::Error>> {
+  let out_dir = PathBuf::from(env::var_os("OUT_DIR").ok_or("OUT_DIR isn't set, is this being run from build.rs?")?);
+
+  let html = match DocumentSource::from(&snapshot.to_string_lossy()) {
+    DocumentSource::File(path) => {
+      println!("cargo:rerun-if-changed={}", path.display());
+      fs::read_to_string(path)?
+    }
+    DocumentSource::Directory(directory) => {
+      // Adding a newer snapshot to the directory changes which one is used
+      println!("cargo:rerun-if-changed={}", directory.display());
+      let latest = source::latest_snapshot(&directory)?;
+      println!("cargo:rerun-if-changed={}", latest.display());
+      fs::read_to_string(latest)?
+    }
+    source => return Err(format!("Build scripts can only generate from local snapshots, not {}", source).into()),
+  };
+
+  write_bindings(&html, options, &out_dir)
+}
+
+fn write_bindings(html: &str, options: &Options, out_dir: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+  let catalog = crate::scrape_document(html)?;
+  for failure in &catalog.failures {
+    println!("cargo:warning=Left out of the bindings: {}", failure);
+  }
+  let options = included_options(options);
+  let generated = crate::generate(&catalog, &options)?;
+
+  let output_dir = out_dir.join(OUT_DIR_NAME);
+  if output_dir.is_dir() {
+    fs::remove_dir_all(&output_dir)?;
+  }
+  generated.write(&output_dir)?;
+
+  let include_file = out_dir.join(OUT_DIR_NAME.to_string() + ".rs");
+  fs::write(&include_file, include_file_contents(&output_dir, generated.files.keys(), &options))?;
+
+  Ok(include_file)
+}
+
+/*
+ * The generated modules end up nested wherever the include file is included, so wrappers reach the execution functions
+ * through the sibling module, and the runtime's paths are taken from the crate root unless they already say otherwise.
+ */
+fn included_options(options: &Options) -> Options {
+  let mut options = options.clone();
+  options.naming.execution_module = "super::super::execution::{{section}}".to_string();
+  for path in [&mut options.runtime.utils, &mut options.runtime.client_configuration] {
+    if !["crate::", "::", "super::", "self::"].iter().any(|prefix| path.starts_with(prefix)) {
+      *path = "crate::".to_string() + path;
+    }
+  }
+
+  options
+}
+
+/*
+ * One module per target, each with one module per section, e.g. wrapper::links_and_comments, importing whatever the
+ * generated code uses without importing itself. include! needs absolute paths to find the files once it's been included
+ * somewhere else.
+ */
+fn include_file_contents<'a>(output_dir: &Path, files: impl Iterator<Item = &'a PathBuf>, options: &Options) -> String {
+  let mut modules: BTreeMap<String, Vec<(String, PathBuf)>> = BTreeMap::new();
+  for file in files {
+    let target = file.iter().next().unwrap().to_string_lossy().to_string();
+    let section = file.file_stem().unwrap().to_string_lossy().to_string();
+    modules.entry(target).or_default().push((section, output_dir.join(file)));
+  }
+  let has_request_models = |section: &str| {
+    modules
+      .get(Target::RequestModels.directory())
+      .is_some_and(|sections| sections.iter().any(|(name, _)| name == section))
+  };
+
+  let mut contents = String::new();
+  for (target, sections) in &modules {
+    contents.push_str(&("pub mod ".to_string() + target + " {\n"));
+    for (section, path) in sections {
+      let mut uses = Vec::new();
+      if target == Target::Execution.directory() {
+        uses.push("handlebars::Handlebars".to_string());
+      } else if target == Target::Wrapper.directory() {
+        uses.push(options.runtime.utils.clone());
+        uses.push("std::collections::HashMap".to_string());
+        if has_request_models(section) {
+          uses.push("super::super::request_models::".to_string() + section + "::*");
+        }
+      }
+
+      contents.push_str(&("  pub mod ".to_string() + section + " {\n"));
+      for used in uses {
+        contents.push_str(&("    #[allow(unused_imports)]\n    use ".to_string() + &used + ";\n"));
+      }
+      contents.push_str(&("    include!(".to_string() + &format!("{:?}", path.display().to_string()) + ");\n"));
+      contents.push_str("  }\n");
+    }
+    contents.push_str("}\n\n");
+  }
+
+  contents
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn the_include_file_parses_as_rust() {
+    let out_dir = std::env::temp_dir().join(format!("reddit_api_scraper_build_script_{}", std::process::id()));
+    let html = fs::read_to_string("fixtures/dev_api.html").unwrap();
+    let include_file = write_bindings(&html, &Options::default(), &out_dir).unwrap();
+
+    // What the compiler sees once every include! has been expanded
+    let mut expanded = String::new();
+    for line in fs::read_to_string(&include_file).unwrap().lines() {
+      match line.trim().strip_prefix("include!(") {
+        Some(path) => {
+          let path: String = syn::parse_str::<syn::LitStr>(path.trim_end_matches(");")).unwrap().value();
+          expanded.push_str(&fs::read_to_string(path).unwrap());
+        }
+        None => expanded.push_str(line),
+      }
+      expanded.push('\n');
+    }
+
+    let file = syn::parse_file(&expanded).unwrap();
+    let modules: Vec<String> = file
+      .items
+      .iter()
+      .filter_map(|item| match item {
+        syn::Item::Mod(module) => Some(module.ident.to_string()),
+        _ => None,
+      })
+      .collect();
+    assert_eq!(modules, ["execution", "overview", "request_models", "wrapper"]);
+    assert!(expanded.contains("super::super::execution::listings::execute_get_hot"));
+
+    fs::remove_dir_all(&out_dir).unwrap();
+  }
+}
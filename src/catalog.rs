@@ -0,0 +1,161 @@
+use crate::http_verb::HttpVerb;
+use crate::scrape_error::ScrapeError;
+use crate::template_uri::TemplateUri;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/*
+ * Everything scraped from one version of the docs, before any code is generated from it. Code is only generated from
+ * the catalog, never from the docs directly, and the catalog can be saved as JSON and generated from again later, so
+ * everything but the sections can be left out of one written by hand.
+ */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiCatalog {
+  #[serde(default)]
+  pub overview: Overview,
+  pub sections: Vec<ApiSection>,
+  // From the OAuth scope pages, if those were scraped too
+  #[serde(default)]
+  pub scopes: Vec<Scope>,
+  // Entries of the docs that couldn't be scraped, and so were left out
+  #[serde(default)]
+  pub failures: Vec<ScrapeError>,
+}
+
+// The material at the top of the docs explaining what the endpoints have in common, rather than any one endpoint
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Overview {
+  // What fullnames are, as Markdown
+  pub fullnames: String,
+  // The kinds of thing a fullname can refer to, told apart by the fullname's prefix
+  pub thing_kinds: Vec<ThingKind>,
+  // How listings work, as Markdown
+  pub listings: String,
+  // The parameters every listing takes
+  pub listing_parameters: Vec<ListingParameter>,
+  // What modhashes are, as Markdown
+  pub modhashes: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThingKind {
+  // E.g. t1_
+  pub prefix: String,
+  // E.g. Comment
+  pub name: String,
+}
+
+// An OAuth scope, as documented on the scope pages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scope {
+  // E.g. read
+  pub name: String,
+  pub description: String,
+  // The anchors of the endpoints the scope gives access to
+  pub endpoints: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListingParameter {
+  pub name: String,
+  pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiSection {
+  pub name: String,
+  pub endpoints: Vec<Endpoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Endpoint {
+  // The id of the endpoint's element in the docs, e.g. GET_wiki_{page}
+  pub anchor: String,
+  pub verb: HttpVerb,
+  // Every concrete URI the endpoint can be called through
+  pub uris: Vec<TemplateUri>,
+  // Documented parameters, in the order the docs list them
+  pub parameters: Vec<Parameter>,
+  pub description: String,
+  // OAuth scopes needed to call the endpoint, e.g. read or modposts
+  pub scopes: Vec<String>,
+  // Whether the endpoint returns a listing that can be paged through
+  pub listing: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Parameter {
+  pub name: String,
+  pub location: Location,
+  // As Markdown
+  pub description: String,
+  // The docs don't give types, so this is a guess from the description
+  #[serde(rename = "type")]
+  pub parameter_type: ParameterType,
+}
+
+// Where a parameter goes in a request
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Location {
+  Uri,
+  Query,
+  Body,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParameterType {
+  String,
+  Integer,
+  Boolean,
+  // E.g. t3_15bfi0, see ThingKind
+  Fullname,
+  // Exactly one of the values listed in the docs, e.g. link or self
+  OneOf(Vec<String>),
+}
+
+impl ApiCatalog {
+  pub fn endpoints(&self) -> impl Iterator<Item = (&ApiSection, &Endpoint)> {
+    self
+      .sections
+      .iter()
+      .flat_map(|section| section.endpoints.iter().map(move |endpoint| (section, endpoint)))
+  }
+}
+
+impl Endpoint {
+  pub fn parameter(&self, name: &str) -> Option<&Parameter> {
+    self.parameters.iter().find(|parameter| parameter.name == name)
+  }
+}
+
+impl ApiSection {
+  // Generated files (and so modules) are named after the section, e.g. "links & comments" -> links_and_comments
+  pub fn file_name(&self) -> String {
+    self.name.replace("&", "and").replace(" ", "_")
+  }
+}
+
+impl fmt::Display for Location {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Location::Uri => write!(f, "URI"),
+      Location::Query => write!(f, "query"),
+      Location::Body => write!(f, "body"),
+    }
+  }
+}
+
+impl fmt::Display for ParameterType {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ParameterType::String => write!(f, "string"),
+      ParameterType::Integer => write!(f, "integer"),
+      ParameterType::Boolean => write!(f, "boolean"),
+      ParameterType::Fullname => write!(f, "fullname"),
+      ParameterType::OneOf(values) => write!(f, "one of {}", values.join(", ")),
+    }
+  }
+}
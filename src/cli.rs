@@ -0,0 +1,181 @@
+use reddit_api_scraper::generator::Target;
+use reddit_api_scraper::layout;
+use reddit_api_scraper::source;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(about = "Scrapes the reddit API documentation and generates Rust bindings from it")]
+pub struct Cli {
+  /// Print more detail about what's being scraped (repeat for more)
+  #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+  pub verbose: u8,
+
+  /// Only print errors
+  #[arg(short, long, global = true, conflicts_with = "verbose")]
+  pub quiet: bool,
+
+  /// Configuration file to use instead of looking for reddit-scraper.toml in this directory and its parents
+  #[arg(short, long, global = true)]
+  pub config: Option<PathBuf>,
+
+  #[command(subcommand)]
+  pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+  /// Download the API documentation page into the snapshot store, if it changed since it was last fetched
+  Fetch {
+    /// Page to download
+    #[arg(long, default_value = source::DEFAULT_URL)]
+    url: String,
+
+    /// Also save a copy of the page to this file
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+  },
+
+  /// List the sections and endpoints found in the documentation, without generating anything
+  Scrape {
+    #[command(flatten)]
+    input: Input,
+
+    /// Also write everything scraped to this file, as JSON
+    #[arg(long)]
+    catalog: Option<PathBuf>,
+  },
+
+  /// Generate bindings from the documentation
+  Generate {
+    #[command(flatten)]
+    input: Input,
+
+    /// Generate from a catalog saved by scrape --catalog instead of from the documentation
+    #[arg(long, conflicts_with_all = ["input", "layout", "scope_pages"])]
+    from: Option<PathBuf>,
+
+    #[command(flatten)]
+    output: Output,
+  },
+
+  /// Report endpoints added, removed or changed between two versions of the documentation, exits with 1 if any were
+  Diff {
+    /// The older documentation: a file, a directory of snapshots, a URL or "-" for stdin
+    old: String,
+
+    /// The newer documentation: a file, a directory of snapshots, a URL or "-" for stdin
+    new: String,
+
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+  },
+
+  /// Trace when each endpoint appeared, changed and disappeared across a directory of dated snapshots
+  History {
+    /// Directory of .html snapshots, named so they sort chronologically (e.g. 2015-06-01.html), or a snapshot store
+    directory: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = HistoryFormat::Markdown)]
+    format: HistoryFormat,
+  },
+
+  /// Keep checking the documentation, regenerating and notifying a webhook whenever its endpoints change
+  Watch {
+    #[command(flatten)]
+    input: Input,
+
+    #[command(flatten)]
+    output: Output,
+
+    /// Seconds between checks [default: 3600, or as configured]
+    #[arg(long)]
+    interval: Option<u64>,
+
+    /// URL to POST a JSON summary of each change to [default: as configured, or none]
+    #[arg(long)]
+    webhook: Option<String>,
+  },
+
+  /// Show how one endpoint is scraped and what's generated for it, step by step
+  Explain {
+    /// The endpoint's anchor in the documentation, e.g. GET_wiki_{page}
+    anchor: String,
+
+    /// Where to read the documentation from: a file, a directory of snapshots, a URL or "-" for stdin
+    #[arg(default_value = source::DEFAULT_URL)]
+    input: String,
+
+    /// Layout of the documentation's markup (current, legacy) [default: detected from the document]
+    #[arg(long, value_parser = parse_layout)]
+    layout: Option<String>,
+
+    /// Which kinds of generated code to show (execution, wrapper, request_models) [default: all, or as configured]
+    #[arg(short, long = "target")]
+    targets: Vec<Target>,
+  },
+
+  /// Check that previously generated bindings are up to date, exits with 1 if they're not
+  Check {
+    #[command(flatten)]
+    input: Input,
+
+    #[command(flatten)]
+    output: Output,
+  },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+  Text,
+  Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum HistoryFormat {
+  Markdown,
+  Json,
+}
+
+#[derive(Debug, Args)]
+pub struct Input {
+  /// Where to read the documentation from: a file, a directory of snapshots, a URL or "-" for stdin
+  #[arg(default_value = source::DEFAULT_URL)]
+  pub input: String,
+
+  /// Layout of the documentation's markup (current, legacy) [default: detected from the document]
+  #[arg(long, value_parser = parse_layout)]
+  pub layout: Option<String>,
+
+  /// OAuth scope pages to scrape too: a file, a directory of pages or a URL (repeatable) [default: as configured]
+  #[arg(long = "scopes")]
+  pub scope_pages: Vec<String>,
+
+  /// Fail if anything in the documentation can't be turned into bindings as documented
+  #[arg(long)]
+  pub strict: bool,
+
+  /// Also write the report of what can't be turned into bindings to this file, as JSON
+  #[arg(long)]
+  pub report: Option<PathBuf>,
+}
+
+fn parse_layout(name: &str) -> Result<String, String> {
+  if layout::LAYOUT_NAMES.contains(&name) {
+    Ok(name.to_string())
+  } else {
+    Err(format!("expected one of: {}", layout::LAYOUT_NAMES.join(", ")))
+  }
+}
+
+#[derive(Debug, Args)]
+pub struct Output {
+  /// Root directory for the generated files [default: target/output, or as configured]
+  #[arg(short, long = "output")]
+  pub output_dir: Option<PathBuf>,
+
+  /// Which kinds of file to generate (execution, wrapper, request_models, overview) [default: all, or as configured]
+  #[arg(short, long = "target")]
+  pub targets: Vec<Target>,
+}
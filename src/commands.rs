@@ -0,0 +1,673 @@
+use crate::cli::{Format, HistoryFormat, Input, Output};
+use crate::watch;
+use crate::watch::ChangeNotification;
+
+use reddit_api_scraper::api_scraper;
+use reddit_api_scraper::catalog::{ApiCatalog, ApiSection};
+use reddit_api_scraper::config::Config;
+use reddit_api_scraper::diff::CatalogDiff;
+use reddit_api_scraper::generator;
+use reddit_api_scraper::generator::Target;
+use reddit_api_scraper::history::{History, SkippedSnapshot};
+use reddit_api_scraper::scopes;
+use reddit_api_scraper::snapshot;
+use reddit_api_scraper::snapshot::{Snapshot, SnapshotStore};
+use reddit_api_scraper::source;
+use reddit_api_scraper::source::DocumentSource;
+use reddit_api_scraper::validation;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+pub type CommandResult = Result<Outcome, Box<dyn std::error::Error>>;
+
+/*
+ * Commands that compare things (diff, check) report whether they found differences through the exit code, so CI and
+ * Makefiles can act on it without parsing output.
+ */
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+  Success,
+  Differences,
+}
+
+// Generation leaves this in the output root, recording exactly which version of the docs the code came from
+const SNAPSHOT_RECORD_FILE_NAME: &str = "snapshot.json";
+
+// The documentation as read from the input, and the stored snapshot it matches, if any
+struct Document {
+  source: String,
+  html: String,
+  snapshot: Option<Snapshot>,
+}
+
+#[derive(Serialize)]
+struct SnapshotRecord<'a> {
+  source: &'a str,
+  sha256: String,
+  snapshot: &'a Option<Snapshot>,
+}
+
+pub async fn fetch(url: &str, output: Option<&Path>, config: &Config) -> CommandResult {
+  let store = SnapshotStore::new(&config.snapshots.directory);
+  let (snapshot, html) = store.fetch(url).await?;
+
+  println!("{} {}", snapshot.sha256, snapshot.fetched_at);
+
+  if let Some(path) = output {
+    fs::write(path, html)?;
+    info!("Saved {} to {}", url, path.display());
+  }
+
+  Ok(Outcome::Success)
+}
+
+pub async fn scrape(input: &Input, catalog_path: Option<&Path>, config: &Config) -> CommandResult {
+  let document = read(&input.input, config).await?;
+
+  let catalog = scrape_input(input, &document, config).await?;
+  if let Some(path) = catalog_path {
+    fs::write(path, serde_json::to_string_pretty(&catalog)? + "\n")?;
+    info!("Saved the catalog to {}", path.display());
+  }
+
+  let overview = &catalog.overview;
+  if !overview.thing_kinds.is_empty() || !overview.listing_parameters.is_empty() {
+    println!("overview");
+    for kind in &overview.thing_kinds {
+      println!("    {} {}", kind.prefix, kind.name);
+    }
+    if !overview.listing_parameters.is_empty() {
+      let names: Vec<&str> = overview.listing_parameters.iter().map(|parameter| parameter.name.as_str()).collect();
+      println!("    listing parameters: {}", names.join(", "));
+    }
+  }
+
+  if !catalog.scopes.is_empty() {
+    println!("scopes");
+    for scope in &catalog.scopes {
+      println!("    {} ({} endpoint(s))", scope.name, scope.endpoints.len());
+    }
+  }
+
+  for section in catalog.sections {
+    println!("{}", section.name);
+    for endpoint in section.endpoints {
+      if endpoint.scopes.is_empty() {
+        println!("    {}", endpoint.anchor);
+      } else {
+        println!("    {} [{}]", endpoint.anchor, endpoint.scopes.join(", "));
+      }
+      for uri in endpoint.uris {
+        println!("        {} {}", endpoint.verb, uri.template());
+      }
+    }
+  }
+
+  Ok(Outcome::Success)
+}
+
+pub async fn generate(input: &Input, from: Option<&Path>, output: &Output, config: &Config) -> CommandResult {
+  let options = generator_options(output, config);
+
+  // A saved catalog stands in for the docs, so the record points at the catalog instead
+  let (catalog, source, sha256, snapshot) = match from {
+    Some(path) => {
+      let json = fs::read_to_string(path)?;
+      let catalog: ApiCatalog = serde_json::from_str(&json)
+        .map_err(|error| format!("{} isn't a valid catalog: {}", path.display(), error))?;
+      report_issues(input, &catalog, config)?;
+      (catalog, path.display().to_string(), snapshot::sha256(&json), None)
+    }
+    None => {
+      let document = read(&input.input, config).await?;
+      let catalog = scrape_input(input, &document, config).await?;
+      (catalog, document.source, snapshot::sha256(&document.html), document.snapshot)
+    }
+  };
+  generator::generate(&catalog, &options)?.write(&options.output_dir)?;
+  write_snapshot_record(&options.output_dir, &source, &sha256, &snapshot)?;
+  info!("Generated bindings in {}", options.output_dir.display());
+
+  Ok(Outcome::Success)
+}
+
+// Whenever bindings are written, so the output root always names the docs it was last generated from
+fn write_snapshot_record(
+  output_dir: &Path,
+  source: &str,
+  sha256: &str,
+  snapshot: &Option<Snapshot>,
+) -> Result<(), Box<dyn std::error::Error>> {
+  let record = SnapshotRecord {
+    source,
+    sha256: sha256.to_string(),
+    snapshot,
+  };
+  fs::write(
+    output_dir.join(SNAPSHOT_RECORD_FILE_NAME),
+    serde_json::to_string_pretty(&record)? + "\n",
+  )?;
+
+  Ok(())
+}
+
+pub async fn diff(old: &str, new: &str, format: Format, config: &Config) -> CommandResult {
+  let old_catalog = api_scraper::scrape_catalog(&read(old, config).await?.html, None, &config.scraping)?;
+  let new_catalog = api_scraper::scrape_catalog(&read(new, config).await?.html, None, &config.scraping)?;
+  report_failures(&old_catalog);
+  report_failures(&new_catalog);
+
+  let diff = CatalogDiff::between(&old_catalog, &new_catalog);
+  match format {
+    Format::Text => print!("{}", diff),
+    Format::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+  }
+
+  if diff.is_empty() {
+    Ok(Outcome::Success)
+  } else {
+    Ok(Outcome::Differences)
+  }
+}
+
+/*
+ * Years of archived copies are bound to include some that aren't the docs at all, or are in a layout that isn't
+ * recognized, so those are skipped rather than losing the history of all the rest.
+ */
+pub async fn history(directory: &Path, format: HistoryFormat, config: &Config) -> CommandResult {
+  let mut snapshots = Vec::new();
+  let mut skipped = Vec::new();
+  for path in source::snapshots_in(directory)? {
+    info!("Scraping {}", path.display());
+    match scrape_snapshot(&path, config) {
+      Ok(snapshot) => snapshots.push(snapshot),
+      Err(error) => {
+        info!("warning: skipping {}: {}", path.display(), error);
+        skipped.push(SkippedSnapshot {
+          path: path.display().to_string(),
+          reason: error.to_string(),
+        });
+      }
+    }
+  }
+  snapshots.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+  let mut history = History::build(&snapshots);
+  history.skipped = skipped;
+  match format {
+    HistoryFormat::Markdown => print!("{}", history.to_markdown()?),
+    HistoryFormat::Json => println!("{}", serde_json::to_string_pretty(&history)?),
+  }
+
+  Ok(Outcome::Success)
+}
+
+fn scrape_snapshot(path: &Path, config: &Config) -> Result<(String, ApiCatalog), Box<dyn std::error::Error>> {
+  let catalog = api_scraper::scrape_catalog(&fs::read_to_string(path)?, None, &config.scraping)?;
+  report_failures(&catalog);
+
+  Ok((snapshot_label(path)?, catalog))
+}
+
+/*
+ * Snapshots from the snapshot store are named after their hash, but have their fetch time recorded next to them.
+ * Anything else is expected to be named after its date.
+ */
+fn snapshot_label(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+  let metadata_path = path.with_extension("json");
+  if metadata_path.is_file() {
+    if let Ok(snapshot) = serde_json::from_str::<Snapshot>(&fs::read_to_string(metadata_path)?) {
+      return Ok(snapshot.fetched_at);
+    }
+  }
+
+  Ok(path.file_stem().unwrap_or_default().to_string_lossy().to_string())
+}
+
+/*
+ * Re-reads the input every interval, but only re-scrapes when its content hash changes, and only regenerates (and
+ * notifies) when that changes the documented endpoints. Errors are reported and retried on the next check rather than
+ * ending the watch.
+ */
+pub async fn watch(
+  input: &Input,
+  output: &Output,
+  interval: Option<u64>,
+  webhook: Option<&str>,
+  config: &Config,
+) -> CommandResult {
+  let interval = Duration::from_secs(interval.unwrap_or(config.watch.interval));
+  let webhook = webhook.or(config.watch.webhook.as_deref());
+
+  let mut watcher = Watcher::start(input, generator_options(output, config), webhook, config).await?;
+  info!("Watching {} every {} second(s)", watcher.source, interval.as_secs());
+
+  loop {
+    tokio::time::sleep(interval).await;
+
+    if let Err(error) = watcher.check().await {
+      eprintln!("error: {}", error);
+    }
+  }
+}
+
+// The docs as watch last generated from them
+struct Watcher<'a> {
+  input: &'a Input,
+  options: generator::Options,
+  webhook: Option<&'a str>,
+  config: &'a Config,
+  source: String,
+  sha256: String,
+  catalog: ApiCatalog,
+}
+
+impl<'a> Watcher<'a> {
+  async fn start(
+    input: &'a Input,
+    options: generator::Options,
+    webhook: Option<&'a str>,
+    config: &'a Config,
+  ) -> Result<Watcher<'a>, Box<dyn std::error::Error>> {
+    let document = read(&input.input, config).await?;
+    let sha256 = snapshot::sha256(&document.html);
+    let catalog = scrape_input(input, &document, config).await?;
+    generator::generate(&catalog, &options)?.write(&options.output_dir)?;
+    write_snapshot_record(&options.output_dir, &document.source, &sha256, &document.snapshot)?;
+
+    Ok(Watcher {
+      input,
+      options,
+      webhook,
+      config,
+      source: document.source,
+      sha256,
+      catalog,
+    })
+  }
+
+  // Anything that goes wrong leaves the watcher as it was, so the same change is picked up again on the next check
+  async fn check(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    let document = read(&self.input.input, self.config).await?;
+
+    let sha256 = snapshot::sha256(&document.html);
+    if sha256 == self.sha256 {
+      debug!("Unchanged ({})", sha256);
+      return Ok(());
+    }
+
+    let catalog = scrape_input(self.input, &document, self.config).await?;
+
+    let diff = CatalogDiff::between(&self.catalog, &catalog);
+    if diff.is_empty() {
+      info!("Content changed ({}) but the endpoints didn't", sha256);
+    } else {
+      info!("Endpoints changed ({})", sha256);
+      print!("{}", diff);
+
+      let output_dir = &self.options.output_dir;
+      generator::generate(&catalog, &self.options)?.write(output_dir)?;
+      write_snapshot_record(output_dir, &document.source, &sha256, &document.snapshot)?;
+
+      if let Some(webhook) = self.webhook {
+        let notification = ChangeNotification {
+          source: &document.source,
+          previous_sha256: &self.sha256,
+          sha256: &sha256,
+          output_dir: output_dir.display().to_string(),
+          diff: &diff,
+        };
+        if let Err(error) = watch::notify(webhook, &notification).await {
+          eprintln!("error: {}", error);
+        }
+      }
+    }
+
+    self.sha256 = sha256;
+    self.catalog = catalog;
+    Ok(())
+  }
+}
+
+pub async fn check(input: &Input, output: &Output, config: &Config) -> CommandResult {
+  let document = read(&input.input, config).await?;
+
+  let options = generator_options(output, config);
+  let output_dir = &options.output_dir;
+  let catalog = scrape_input(input, &document, config).await?;
+
+  let stale_files = generator::generate(&catalog, &options)?.stale_files(output_dir)?;
+  for file in &stale_files {
+    println!("{}", file.display());
+  }
+
+  if stale_files.is_empty() {
+    info!("{} is up to date", output_dir.display());
+    Ok(Outcome::Success)
+  } else {
+    info!("{} file(s) in {} are out of date", stale_files.len(), output_dir.display());
+    Ok(Outcome::Differences)
+  }
+}
+
+/*
+ * Everything between the docs and the generated code for one endpoint: the element documenting it, its URIs and what
+ * their optional parts expand into, its parameters, the names it's generated under and the code itself.
+ */
+pub async fn explain(
+  anchor: &str,
+  input: &str,
+  layout: Option<&str>,
+  targets: &[Target],
+  config: &Config,
+) -> CommandResult {
+  let document = read(input, config).await?;
+
+  let trace = api_scraper::trace_endpoint(&document.html, anchor, layout, &config.scraping)?
+    .ok_or_else(|| format!("{} isn't in the documentation's table of contents", anchor))?;
+
+  println!("section: {}", trace.section);
+
+  println!();
+  println!("element:");
+  match &trace.html {
+    Some(html) => println!("{}", html),
+    None => println!("    none, the anchor doesn't lead anywhere"),
+  }
+
+  println!();
+  println!("URIs:");
+  for (prototype, concrete) in &trace.uris {
+    println!("    {}", prototype);
+    match concrete {
+      Ok(uris) => {
+        for uri in uris {
+          println!("        -> {}", uri.template());
+        }
+      }
+      Err(reason) => println!("        malformed: {}", reason),
+    }
+  }
+
+  let endpoint = trace.endpoint?;
+
+  println!();
+  println!("parameters:");
+  for parameter in &endpoint.parameters {
+    println!("    {} ({}): {}", parameter.name, parameter.location, parameter.parameter_type);
+    for line in parameter.description.lines() {
+      println!("        {}", line);
+    }
+  }
+
+  let mut options = config.generator_options();
+  if !targets.is_empty() {
+    options.targets = targets.to_vec();
+  }
+  let section = ApiSection {
+    name: trace.section,
+    endpoints: vec![endpoint],
+  };
+  let endpoint = &section.endpoints[0];
+
+  println!();
+  println!("names:");
+  if !generator::supports(&endpoint.verb) {
+    println!("    none, nothing is generated for {} yet", endpoint.verb);
+    return Ok(Outcome::Success);
+  }
+  for uri in &endpoint.uris {
+    println!("    {}", uri.template());
+    for (what, name) in generator::derived_names(endpoint, uri, &section.file_name(), &options)? {
+      println!("        {}: {}", what, name);
+    }
+  }
+
+  // As though it were the only endpoint in its section
+  let catalog = ApiCatalog {
+    sections: vec![section],
+    ..ApiCatalog::default()
+  };
+  for (path, contents) in generator::generate(&catalog, &options)?.files {
+    println!();
+    println!("{}:", path.display());
+    print!("{}", contents);
+  }
+
+  Ok(Outcome::Success)
+}
+
+// Entries left out of the catalog are worth knowing about, but don't stop anything being done with the rest
+fn report_failures(catalog: &ApiCatalog) {
+  for failure in &catalog.failures {
+    info!("warning: {}", failure);
+  }
+}
+
+/*
+ * Scrapes the docs, along with the OAuth scope pages if there are any, warning about anything that won't make it into
+ * the bindings as documented and anything the scope pages and the endpoints' scope badges disagree on.
+ */
+async fn scrape_input(input: &Input, document: &Document, config: &Config) -> Result<ApiCatalog, Box<dyn std::error::Error>> {
+  let mut catalog = api_scraper::scrape_catalog(&document.html, input.layout.as_deref(), &config.scraping)?;
+  report_issues(input, &catalog, config)?;
+
+  let scope_pages = if input.scope_pages.is_empty() {
+    &config.scopes.pages
+  } else {
+    &input.scope_pages
+  };
+  if scope_pages.is_empty() {
+    return Ok(catalog);
+  }
+
+  let mut pages = Vec::new();
+  for scope_page in scope_pages {
+    // Every page in a directory, rather than just the latest as for the docs themselves
+    let sources = match DocumentSource::from(scope_page) {
+      DocumentSource::Directory(directory) => source::snapshots_in(&directory)?
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect(),
+      _ => vec![scope_page.clone()],
+    };
+
+    for source in sources {
+      let page = read(&source, config).await?;
+      pages.push(api_scraper::scrape_scopes(&page.html, None, &config.scraping)?);
+    }
+  }
+  catalog.scopes = scopes::merge(pages);
+
+  for mismatch in scopes::cross_check(&catalog) {
+    info!("warning: {}", mismatch);
+  }
+
+  Ok(catalog)
+}
+
+// Anything that won't make it into the bindings as documented, failing with --strict if there's anything at all
+fn report_issues(input: &Input, catalog: &ApiCatalog, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+  let report = validation::validate(catalog, &config.fields);
+  for issue in &report.issues {
+    info!("warning: {}", issue);
+  }
+  if let Some(path) = &input.report {
+    fs::write(path, serde_json::to_string_pretty(&report)? + "\n")?;
+  }
+  if input.strict && !report.is_empty() {
+    return Err(format!("{} problem(s) found in the docs, failing because of --strict", report.issues.len()).into());
+  }
+
+  Ok(())
+}
+
+/*
+ * URLs are fetched through the snapshot store, so an unchanged page is only downloaded once. Anything else is read as
+ * is, but still matched up with the store in case it's a copy of a snapshot fetched earlier.
+ */
+async fn read(input: &str, config: &Config) -> Result<Document, Box<dyn std::error::Error>> {
+  let source = DocumentSource::from(input);
+  info!("Reading API documentation from {}", source);
+
+  let store = SnapshotStore::new(&config.snapshots.directory);
+  let (html, snapshot) = match &source {
+    DocumentSource::Url(url) => {
+      let (snapshot, html) = store.fetch(url).await?;
+      (html, Some(snapshot))
+    }
+    _ => {
+      let html = source.read().await?;
+      let snapshot = store.find(&snapshot::sha256(&html))?;
+      (html, snapshot)
+    }
+  };
+
+  Ok(Document {
+    source: source.to_string(),
+    html,
+    snapshot,
+  })
+}
+
+// Options from the configuration file, with anything given on the command line taking precedence
+fn generator_options(output: &Output, config: &Config) -> generator::Options {
+  let mut options = config.generator_options();
+
+  if let Some(output_dir) = &output.output_dir {
+    options.output_dir = output_dir.clone();
+  }
+
+  if !output.targets.is_empty() {
+    options.targets = output.targets.clone();
+  }
+
+  options
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::path::PathBuf;
+  use std::sync::{Arc, Mutex};
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+  use tokio::net::{TcpListener, TcpStream};
+
+  // What the stand-in has been asked for: how many times the docs were fetched, and the body of each webhook POST
+  #[derive(Default)]
+  struct Requests {
+    gets: usize,
+    posts: Vec<String>,
+  }
+
+  /*
+   * Stands in for both reddit and the webhook: each GET is answered with the next of the pages, the last one over and
+   * over once they run out, and each POST is recorded.
+   */
+  async fn serve(listener: TcpListener, pages: Vec<String>, requests: Arc<Mutex<Requests>>) {
+    loop {
+      let (mut stream, _) = listener.accept().await.unwrap();
+      let (head, body) = read_request(&mut stream).await;
+
+      let response = if head.starts_with("POST") {
+        requests.lock().unwrap().posts.push(body);
+        "HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n".to_string()
+      } else {
+        let mut requests = requests.lock().unwrap();
+        let page = &pages[requests.gets.min(pages.len() - 1)];
+        requests.gets += 1;
+        format!(
+          "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+          page.len(),
+          page
+        )
+      };
+      stream.write_all(response.as_bytes()).await.unwrap();
+    }
+  }
+
+  async fn read_request(stream: &mut TcpStream) -> (String, String) {
+    let mut request = Vec::new();
+    let mut buffer = [0; 4096];
+    loop {
+      let read = stream.read(&mut buffer).await.unwrap();
+      request.extend_from_slice(&buffer[..read]);
+
+      let request = String::from_utf8_lossy(&request).to_string();
+      if let Some(end) = request.find("\r\n\r\n") {
+        let head = request[..end].to_string();
+        let length = head
+          .lines()
+          .find_map(|line| line.to_lowercase().strip_prefix("content-length: ").map(|length| length.to_string()))
+          .map_or(0, |length| length.trim().parse().unwrap());
+        if request.len() >= end + 4 + length {
+          return (head, request[end + 4..].to_string());
+        }
+      }
+      assert!(read > 0, "the connection closed before the end of the request");
+    }
+  }
+
+  fn scratch_directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("reddit_api_scraper_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    directory
+  }
+
+  #[tokio::test]
+  async fn watch_notifies_once_for_each_change_to_the_endpoints() {
+    let docs = fs::read_to_string("fixtures/dev_api.html").unwrap();
+    let changed = docs.replace("Returns the identity of the user.", "Returns the identity of the signed in user.");
+    // Different content, same endpoints
+    let restyled = changed.replace("</body>", "<!-- restyled --></body>");
+    let pages = vec![docs, changed.clone(), changed.clone(), restyled];
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let requests = Arc::new(Mutex::new(Requests::default()));
+    let server = tokio::spawn(serve(listener, pages, requests.clone()));
+
+    let directory = scratch_directory("watch");
+    let mut config = Config::default();
+    config.snapshots.directory = directory.join("snapshots");
+    let mut options = config.generator_options();
+    options.output_dir = directory.join("output");
+    let input = Input {
+      input: format!("http://{}/dev/api", address),
+      layout: None,
+      scope_pages: Vec::new(),
+      strict: false,
+      report: None,
+    };
+    let webhook = format!("http://{}/hook", address);
+
+    let mut watcher = Watcher::start(&input, options, Some(&webhook), &config).await.unwrap();
+    assert!(requests.lock().unwrap().posts.is_empty());
+
+    // The endpoint's description changed
+    watcher.check().await.unwrap();
+    let posts = requests.lock().unwrap().posts.clone();
+    assert_eq!(posts.len(), 1);
+    let notification: serde_json::Value = serde_json::from_str(&posts[0]).unwrap();
+    assert_eq!(notification["sha256"], snapshot::sha256(&changed));
+    assert_eq!(notification["diff"]["changed"][0]["anchor"], "GET_api_v1_me");
+    assert!(notification["diff"]["added"].as_array().unwrap().is_empty());
+    assert!(notification["diff"]["removed"].as_array().unwrap().is_empty());
+
+    let record = fs::read_to_string(directory.join("output").join(SNAPSHOT_RECORD_FILE_NAME)).unwrap();
+    let record: serde_json::Value = serde_json::from_str(&record).unwrap();
+    assert_eq!(record["sha256"], snapshot::sha256(&changed));
+
+    // Unchanged, then changed without changing the endpoints
+    watcher.check().await.unwrap();
+    watcher.check().await.unwrap();
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.gets, 4);
+    assert_eq!(requests.posts.len(), 1);
+
+    server.abort();
+    fs::remove_dir_all(&directory).unwrap();
+  }
+}
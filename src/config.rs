@@ -0,0 +1,288 @@
+use crate::generator;
+use crate::generator::Target;
+use crate::snapshot;
+use crate::source::DocumentSource;
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE_NAME: &str = "reddit-scraper.toml";
+
+/*
+ * Everything about the shape of the generated code that differs between projects. Any setting left out of the file
+ * keeps its default, which matches the layout the generator has always produced.
+ */
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+  pub base_url: String,
+  pub output: Output,
+  pub naming: Naming,
+  pub runtime: Runtime,
+  pub fields: Fields,
+  pub snapshots: Snapshots,
+  pub watch: Watch,
+  pub scopes: Scopes,
+  pub scraping: Scraping,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Output {
+  pub directory: Option<PathBuf>,
+  pub targets: Option<Vec<Target>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Naming {
+  // Handlebars template for the module wrappers call the execution functions through, given the section's file name
+  pub execution_module: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Runtime {
+  // Module providing execute_get_api and execute_with_refresh
+  pub utils: String,
+  // Type holding the client ID, secret etc. needed to refresh an access token
+  pub client_configuration: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Fields {
+  // Documented parameters that shouldn't end up in request models
+  pub skip: Vec<String>,
+  // Documented parameter names that aren't usable as Rust identifiers, and the identifier to use instead
+  pub rename: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Snapshots {
+  // Where fetched copies of the docs are kept
+  pub directory: PathBuf,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Watch {
+  // Seconds between checks
+  pub interval: u64,
+  // URL a JSON summary of each change is POSTed to
+  pub webhook: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scopes {
+  // OAuth scope pages to scrape along with the docs: files, directories of pages or URLs
+  pub pages: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scraping {
+  // Scraping fails before it starts unless at least this many endpoints are documented with a URI
+  pub min_endpoints: usize,
+  pub selectors: LayoutSelectors,
+}
+
+/*
+ * Replacements for the CSS selectors of each layout, for when the markup has changed. Each layout has its own, so
+ * keeping up with a restyled live page doesn't change how archived copies are scraped.
+ */
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutSelectors {
+  pub current: Selectors,
+  pub legacy: Selectors,
+}
+
+// Replacements for one layout's selectors
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Selectors {
+  pub sidebar: Option<String>,
+  pub sections: Option<String>,
+  pub endpoints: Option<String>,
+  pub uri_variants: Option<String>,
+  pub uri: Option<String>,
+  pub parameters: Option<String>,
+  pub description: Option<String>,
+  pub scopes: Option<String>,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Config {
+      base_url: "https://oauth.reddit.com".to_string(),
+      output: Output::default(),
+      naming: Naming::default(),
+      runtime: Runtime::default(),
+      fields: Fields::default(),
+      snapshots: Snapshots::default(),
+      watch: Watch::default(),
+      scopes: Scopes::default(),
+      scraping: Scraping::default(),
+    }
+  }
+}
+
+impl Default for Naming {
+  fn default() -> Self {
+    Naming {
+      execution_module: "{{section}}_execution".to_string(),
+    }
+  }
+}
+
+impl Default for Snapshots {
+  fn default() -> Self {
+    Snapshots {
+      directory: PathBuf::from(snapshot::DEFAULT_SNAPSHOT_DIR),
+    }
+  }
+}
+
+impl Default for Watch {
+  fn default() -> Self {
+    Watch {
+      interval: 3600,
+      webhook: None,
+    }
+  }
+}
+
+impl Default for Scraping {
+  fn default() -> Self {
+    Scraping {
+      min_endpoints: 1,
+      selectors: LayoutSelectors::default(),
+    }
+  }
+}
+
+impl Default for Runtime {
+  fn default() -> Self {
+    Runtime {
+      utils: "crate::api::utils".to_string(),
+      client_configuration: "client::ClientConfiguration".to_string(),
+    }
+  }
+}
+
+impl Default for Fields {
+  fn default() -> Self {
+    let mut rename = BTreeMap::new();
+    rename.insert("g-recaptcha-response".to_string(), "g_recaptcha_response".to_string());
+    rename.insert("type".to_string(), "r#type".to_string());
+    rename.insert("mod".to_string(), "r#mod".to_string());
+    // This isn't right, it's supposed to be a username as the JSON property
+    rename.insert("('user',)".to_string(), "user".to_string());
+
+    Fields {
+      // We don't need this, as we're using OAuth
+      skip: vec!["uh / X-Modhash header".to_string()],
+      rename,
+    }
+  }
+}
+
+impl Config {
+  pub fn load(path: &Path) -> Result<Config, Box<dyn std::error::Error>> {
+    let contents = fs::read_to_string(path)?;
+    let mut config: Config = toml::from_str(&contents)
+      .map_err(|error| format!("Invalid configuration in {}: {}", path.display(), error))?;
+
+    config.resolve_paths(path.parent().unwrap_or_else(|| Path::new("")));
+    Ok(config)
+  }
+
+  /*
+   * Relative paths in the file, and the default directories, are relative to the file rather than to wherever the tool
+   * happens to be run from. Paths given on the command line are still relative to the working directory.
+   */
+  fn resolve_paths(&mut self, base: &Path) {
+    let output_dir = self.output.directory.take().unwrap_or_else(|| PathBuf::from(generator::DEFAULT_OUTPUT_DIR));
+    self.output.directory = Some(base.join(output_dir));
+    self.snapshots.directory = base.join(&self.snapshots.directory);
+
+    for page in &mut self.scopes.pages {
+      if let DocumentSource::File(path) | DocumentSource::Directory(path) = DocumentSource::from(page) {
+        *page = base.join(path).to_string_lossy().to_string();
+      }
+    }
+  }
+
+  /*
+   * Like cargo and rustfmt, look for the configuration file in the working directory and then each of its parents, so
+   * the tool behaves the same from anywhere inside a project. No file at all just means the defaults.
+   */
+  pub fn discover() -> Result<Config, Box<dyn std::error::Error>> {
+    match find(&std::env::current_dir()?) {
+      Some(path) => {
+        debug!("Using configuration from {}", path.display());
+        Config::load(&path)
+      }
+      None => Ok(Config::default()),
+    }
+  }
+
+  pub fn generator_options(&self) -> generator::Options {
+    generator::Options {
+      output_dir: match &self.output.directory {
+        Some(directory) => directory.clone(),
+        None => PathBuf::from(generator::DEFAULT_OUTPUT_DIR),
+      },
+      targets: match &self.output.targets {
+        Some(targets) => targets.clone(),
+        None => Target::ALL.to_vec(),
+      },
+      base_url: self.base_url.trim_end_matches('/').to_string(),
+      naming: self.naming.clone(),
+      runtime: self.runtime.clone(),
+      fields: self.fields.clone(),
+    }
+  }
+}
+
+fn find(directory: &Path) -> Option<PathBuf> {
+  directory
+    .ancestors()
+    .map(|ancestor| ancestor.join(CONFIG_FILE_NAME))
+    .find(|candidate| candidate.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn relative_paths_are_relative_to_the_file() {
+    let directory = std::env::temp_dir().join(format!("reddit_api_scraper_config_{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let path = directory.join(CONFIG_FILE_NAME);
+    fs::write(
+      &path,
+      "[output]\ndirectory = \"src/api\"\n\n[scopes]\npages = [\"docs/oauth.html\", \"https://www.reddit.com/dev/api/oauth\"]\n",
+    )
+    .unwrap();
+
+    let config = Config::load(&path).unwrap();
+    assert_eq!(config.generator_options().output_dir, directory.join("src/api"));
+    assert_eq!(config.snapshots.directory, directory.join(snapshot::DEFAULT_SNAPSHOT_DIR));
+    assert_eq!(
+      config.scopes.pages,
+      [
+        directory.join("docs/oauth.html").to_string_lossy().to_string(),
+        "https://www.reddit.com/dev/api/oauth".to_string()
+      ]
+    );
+
+    fs::remove_dir_all(&directory).unwrap();
+  }
+}
@@ -0,0 +1,396 @@
+use crate::catalog::{ApiCatalog, ApiSection, Endpoint};
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/*
+ * What changed in the documented API between two versions of the docs. Endpoints are matched up by their anchor
+ * (verb and path, e.g. GET_wiki_{page}), so an endpoint that moved to a different section is a change, not a removal
+ * and an addition.
+ */
+#[derive(Debug, Default, Serialize)]
+pub struct CatalogDiff {
+  pub added: Vec<EndpointSummary>,
+  pub removed: Vec<EndpointSummary>,
+  pub changed: Vec<EndpointChanges>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EndpointSummary {
+  pub anchor: String,
+  pub section: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EndpointChanges {
+  pub anchor: String,
+  pub section: String,
+  pub changes: Vec<Change>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Change {
+  UriAdded { uri: String },
+  UriRemoved { uri: String },
+  ParameterAdded { name: String },
+  ParameterRemoved { name: String },
+  ParameterDescriptionChanged { name: String, old: String, new: String },
+  DescriptionChanged { old: String, new: String },
+  ScopesChanged { old: Vec<String>, new: Vec<String> },
+  Moved { from: String, to: String },
+}
+
+impl CatalogDiff {
+  pub fn between(old: &ApiCatalog, new: &ApiCatalog) -> CatalogDiff {
+    let old_endpoints = by_anchor(old);
+    let new_endpoints = by_anchor(new);
+
+    let mut diff = CatalogDiff::default();
+    for (anchor, (section, _)) in &old_endpoints {
+      if !new_endpoints.contains_key(anchor) {
+        diff.removed.push(EndpointSummary {
+          anchor: anchor.to_string(),
+          section: section.name.clone(),
+        });
+      }
+    }
+
+    for (anchor, (new_section, new_endpoint)) in &new_endpoints {
+      match old_endpoints.get(anchor) {
+        None => diff.added.push(EndpointSummary {
+          anchor: anchor.to_string(),
+          section: new_section.name.clone(),
+        }),
+        Some((old_section, old_endpoint)) => {
+          let changes = endpoint_changes(old_section, old_endpoint, new_section, new_endpoint);
+          if !changes.is_empty() {
+            diff.changed.push(EndpointChanges {
+              anchor: anchor.to_string(),
+              section: new_section.name.clone(),
+              changes,
+            });
+          }
+        }
+      }
+    }
+
+    diff
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+  }
+}
+
+fn by_anchor(catalog: &ApiCatalog) -> BTreeMap<&str, (&ApiSection, &Endpoint)> {
+  catalog
+    .endpoints()
+    .map(|(section, endpoint)| (endpoint.anchor.as_str(), (section, endpoint)))
+    .collect()
+}
+
+pub(crate) fn endpoint_changes(
+  old_section: &ApiSection,
+  old: &Endpoint,
+  new_section: &ApiSection,
+  new: &Endpoint,
+) -> Vec<Change> {
+  let mut changes = Vec::new();
+
+  if old_section.name != new_section.name {
+    changes.push(Change::Moved {
+      from: old_section.name.clone(),
+      to: new_section.name.clone(),
+    });
+  }
+
+  let old_uris: BTreeSet<_> = old.uris.iter().map(|uri| uri.template()).collect();
+  let new_uris: BTreeSet<_> = new.uris.iter().map(|uri| uri.template()).collect();
+  for uri in new_uris.difference(&old_uris) {
+    changes.push(Change::UriAdded { uri: uri.to_string() });
+  }
+  for uri in old_uris.difference(&new_uris) {
+    changes.push(Change::UriRemoved { uri: uri.to_string() });
+  }
+
+  let old_parameters: BTreeMap<_, _> = old
+    .parameters
+    .iter()
+    .map(|parameter| (&parameter.name, &parameter.description))
+    .collect();
+  let new_parameters: BTreeMap<_, _> = new
+    .parameters
+    .iter()
+    .map(|parameter| (&parameter.name, &parameter.description))
+    .collect();
+  for (name, new_description) in &new_parameters {
+    match old_parameters.get(name) {
+      None => changes.push(Change::ParameterAdded { name: name.to_string() }),
+      Some(old_description) if old_description != new_description => {
+        changes.push(Change::ParameterDescriptionChanged {
+          name: name.to_string(),
+          old: old_description.to_string(),
+          new: new_description.to_string(),
+        })
+      }
+      Some(_) => {}
+    }
+  }
+  for name in old_parameters.keys() {
+    if !new_parameters.contains_key(name) {
+      changes.push(Change::ParameterRemoved { name: name.to_string() });
+    }
+  }
+
+  if old.description != new.description {
+    changes.push(Change::DescriptionChanged {
+      old: old.description.clone(),
+      new: new.description.clone(),
+    });
+  }
+
+  if old.scopes != new.scopes {
+    changes.push(Change::ScopesChanged {
+      old: old.scopes.clone(),
+      new: new.scopes.clone(),
+    });
+  }
+
+  changes
+}
+
+impl fmt::Display for CatalogDiff {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for endpoint in &self.removed {
+      writeln!(f, "- {} ({})", endpoint.anchor, endpoint.section)?;
+    }
+    for endpoint in &self.added {
+      writeln!(f, "+ {} ({})", endpoint.anchor, endpoint.section)?;
+    }
+    for endpoint in &self.changed {
+      writeln!(f, "~ {} ({})", endpoint.anchor, endpoint.section)?;
+      for change in &endpoint.changes {
+        writeln!(f, "    {}", change)?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/*
+ * Each change is a single line: descriptions are Markdown and often run over several lines, so they're quoted with
+ * their line breaks escaped.
+ */
+impl fmt::Display for Change {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Change::UriAdded { uri } => write!(f, "+ URI {}", uri),
+      Change::UriRemoved { uri } => write!(f, "- URI {}", uri),
+      Change::ParameterAdded { name } => write!(f, "+ parameter {}", name),
+      Change::ParameterRemoved { name } => write!(f, "- parameter {}", name),
+      Change::ParameterDescriptionChanged { name, old, new } => {
+        write!(f, "~ parameter {}: {:?} -> {:?}", name, old, new)
+      }
+      Change::DescriptionChanged { old, new } => write!(f, "~ description: {:?} -> {:?}", old, new),
+      Change::ScopesChanged { old, new } => write!(f, "~ scopes: {} -> {}", scope_list(old), scope_list(new)),
+      Change::Moved { from, to } => write!(f, "~ moved from {} to {}", from, to),
+    }
+  }
+}
+
+fn scope_list(scopes: &[String]) -> String {
+  if scopes.is_empty() {
+    "(none)".to_string()
+  } else {
+    scopes.join(", ")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::catalog::{Location, Parameter, ParameterType};
+  use crate::http_verb::HttpVerb;
+  use crate::template_uri::{TemplateUri, UriPrototype};
+
+  fn endpoint(anchor: &str, uris: &[&str], parameters: &[(&str, &str)]) -> Endpoint {
+    Endpoint {
+      anchor: anchor.to_string(),
+      verb: HttpVerb::GET,
+      uris: uris
+        .iter()
+        .map(|uri| TemplateUri {
+          segments: UriPrototype::parse(uri).unwrap().segments,
+        })
+        .collect(),
+      parameters: parameters
+        .iter()
+        .map(|(name, description)| Parameter {
+          name: name.to_string(),
+          location: Location::Query,
+          description: description.to_string(),
+          parameter_type: ParameterType::String,
+        })
+        .collect(),
+      description: String::new(),
+      scopes: Vec::new(),
+      listing: false,
+    }
+  }
+
+  fn catalog(sections: Vec<(&str, Vec<Endpoint>)>) -> ApiCatalog {
+    ApiCatalog {
+      sections: sections
+        .into_iter()
+        .map(|(name, endpoints)| ApiSection {
+          name: name.to_string(),
+          endpoints,
+        })
+        .collect(),
+      ..ApiCatalog::default()
+    }
+  }
+
+  fn anchors(summaries: &[EndpointSummary]) -> Vec<(&str, &str)> {
+    summaries
+      .iter()
+      .map(|summary| (summary.anchor.as_str(), summary.section.as_str()))
+      .collect()
+  }
+
+  // The changes to the only changed endpoint, which must be the one with the given anchor
+  fn only_changes<'a>(diff: &'a CatalogDiff, anchor: &str) -> &'a [Change] {
+    assert!(diff.added.is_empty() && diff.removed.is_empty());
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].anchor, anchor);
+    &diff.changed[0].changes
+  }
+
+  #[test]
+  fn nothing_changed() {
+    let old = catalog(vec![(
+      "account",
+      vec![endpoint("GET_api_v1_me", &["/api/v1/me"], &[("raw_json", "boolean")])],
+    )]);
+
+    let diff = CatalogDiff::between(&old, &old.clone());
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "");
+  }
+
+  #[test]
+  fn endpoints_added_and_removed() {
+    let old = catalog(vec![("account", vec![endpoint("GET_api_v1_me", &["/api/v1/me"], &[])])]);
+    let new = catalog(vec![(
+      "account",
+      vec![endpoint("GET_api_v1_me_karma", &["/api/v1/me/karma"], &[])],
+    )]);
+
+    let diff = CatalogDiff::between(&old, &new);
+    assert_eq!(anchors(&diff.added), [("GET_api_v1_me_karma", "account")]);
+    assert_eq!(anchors(&diff.removed), [("GET_api_v1_me", "account")]);
+    assert!(diff.changed.is_empty());
+  }
+
+  #[test]
+  fn an_endpoint_moved_to_another_section_is_a_change() {
+    let old = catalog(vec![("account", vec![endpoint("GET_api_v1_me", &["/api/v1/me"], &[])])]);
+    let new = catalog(vec![
+      ("account", vec![]),
+      ("users", vec![endpoint("GET_api_v1_me", &["/api/v1/me"], &[])]),
+    ]);
+
+    let diff = CatalogDiff::between(&old, &new);
+    assert_eq!(
+      only_changes(&diff, "GET_api_v1_me"),
+      [Change::Moved {
+        from: "account".to_string(),
+        to: "users".to_string(),
+      }]
+    );
+    assert_eq!(diff.changed[0].section, "users");
+  }
+
+  #[test]
+  fn uris_added_and_removed() {
+    let old = catalog(vec![(
+      "wiki",
+      vec![endpoint("GET_wiki_{page}", &["/wiki/{{page}}", "/r/{{subreddit}}/wiki/{{page}}"], &[])],
+    )]);
+    let new = catalog(vec![(
+      "wiki",
+      vec![endpoint("GET_wiki_{page}", &["/wiki/{{page}}", "/w/{{page}}"], &[])],
+    )]);
+
+    let diff = CatalogDiff::between(&old, &new);
+    assert_eq!(
+      only_changes(&diff, "GET_wiki_{page}"),
+      [
+        Change::UriAdded {
+          uri: "/w/{{page}}".to_string()
+        },
+        Change::UriRemoved {
+          uri: "/r/{{subreddit}}/wiki/{{page}}".to_string()
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn parameters_added_removed_and_redescribed() {
+    let old = catalog(vec![(
+      "search",
+      vec![endpoint("GET_search", &["/search"], &[("q", "a string"), ("sort", "one of (relevance, new)")])],
+    )]);
+    let new = catalog(vec![(
+      "search",
+      vec![endpoint(
+        "GET_search",
+        &["/search"],
+        &[("q", "a string no longer than 512 characters"), ("type", "comma-delimited list")],
+      )],
+    )]);
+
+    let diff = CatalogDiff::between(&old, &new);
+    assert_eq!(
+      only_changes(&diff, "GET_search"),
+      [
+        Change::ParameterDescriptionChanged {
+          name: "q".to_string(),
+          old: "a string".to_string(),
+          new: "a string no longer than 512 characters".to_string(),
+        },
+        Change::ParameterAdded {
+          name: "type".to_string()
+        },
+        Change::ParameterRemoved {
+          name: "sort".to_string()
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn changes_are_displayed_on_one_line_each() {
+    let scopes = Change::ScopesChanged {
+      old: Vec::new(),
+      new: vec!["read".to_string()],
+    };
+    assert_eq!(scopes.to_string(), "~ scopes: (none) -> read");
+
+    let description = Change::DescriptionChanged {
+      old: "Get the \"identity\" of the user.".to_string(),
+      new: "Get the identity of the user.\n\n- `raw_json`: boolean".to_string(),
+    };
+    assert_eq!(
+      description.to_string(),
+      concat!(
+        r#"~ description: "Get the \"identity\" of the user." -> "#,
+        r#""Get the identity of the user.\n\n- `raw_json`: boolean""#
+      )
+    );
+  }
+}
@@ -0,0 +1,46 @@
+use scraper::element_ref::ElementRef;
+use scraper::Html;
+use std::collections::HashMap;
+
+/*
+ * Every element with an id, found in one walk over the document. The table of contents links to every endpoint by id,
+ * so looking them up here saves searching the whole document again for each one.
+ */
+pub struct DocumentIndex<'a> {
+  document: &'a Html,
+  ids: HashMap<&'a str, ElementRef<'a>>,
+}
+
+impl<'a> DocumentIndex<'a> {
+  pub fn new(document: &'a Html) -> DocumentIndex<'a> {
+    let mut ids = HashMap::new();
+    for node in document.tree.nodes() {
+      if let Some(element) = ElementRef::wrap(node) {
+        if let Some(id) = element.value().id() {
+          // There should be only one, but if not the first is what a selector would have found
+          ids.entry(id).or_insert(element);
+        }
+      }
+    }
+
+    DocumentIndex { document, ids }
+  }
+
+  pub fn document(&self) -> &'a Html {
+    self.document
+  }
+
+  pub fn by_id(&self, id: &str) -> Option<ElementRef<'a>> {
+    self.ids.get(id).copied()
+  }
+
+  // The element a link leads to, e.g. #section_account or https://web.archive.org/web/.../dev/api#section_account
+  pub fn by_anchor(&self, href: &str) -> Option<ElementRef<'a>> {
+    let anchor = match href.rfind('#') {
+      Some(i) => &href[i + 1..],
+      None => href,
+    };
+
+    self.by_id(anchor)
+  }
+}
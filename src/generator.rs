@@ -1,38 +1,271 @@
+use crate::catalog::{ApiCatalog, Endpoint, Location, Overview, Parameter, Scope};
+use crate::config;
 use crate::http_verb::HttpVerb;
+use crate::source::DEFAULT_URL;
 use crate::template_uri;
 
 use handlebars::Handlebars;
 use inflector::Inflector;
-use std::collections::HashMap;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
 use std::fs;
 use std::io::prelude::*;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str;
 
-pub fn write_get_api(api: &template_uri::TemplateUri, mut file: &fs::File) -> Result<(), Box<dyn std::error::Error>> {
-  let api_method_name = str::replace(
-    &api
-      .template
-      .trim_start_matches('/')
-      .trim_end_matches('/')
-      .replace("{", "")
-      .replace("}", ""),
-    "/",
-    "_",
-  );
+pub const DEFAULT_OUTPUT_DIR: &str = "target/output";
+
+/*
+ * The kinds of file generated for each API section, each in its own directory under the output root, plus the types
+ * shared by every section that are generated from the overview.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Target {
+  Execution,
+  Wrapper,
+  RequestModels,
+  Overview,
+}
+
+impl Target {
+  pub const ALL: [Target; 4] = [Target::Execution, Target::Wrapper, Target::RequestModels, Target::Overview];
+
+  pub fn directory(&self) -> &'static str {
+    match self {
+      Target::Execution => "execution",
+      Target::Wrapper => "wrapper",
+      Target::RequestModels => "request_models",
+      Target::Overview => "overview",
+    }
+  }
+}
+
+impl str::FromStr for Target {
+  type Err = String;
+
+  fn from_str(string: &str) -> Result<Self, Self::Err> {
+    match string {
+      "execution" => Ok(Target::Execution),
+      "wrapper" => Ok(Target::Wrapper),
+      "request_models" | "request-models" => Ok(Target::RequestModels),
+      "overview" => Ok(Target::Overview),
+      _ => Err(format!(
+        "Unknown target '{}', expected one of: execution, wrapper, request_models, overview",
+        string
+      )),
+    }
+  }
+}
+
+impl fmt::Display for Target {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.directory())
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+  pub output_dir: PathBuf,
+  pub targets: Vec<Target>,
+  pub base_url: String,
+  pub naming: config::Naming,
+  pub runtime: config::Runtime,
+  pub fields: config::Fields,
+}
+
+impl Options {
+  pub fn emits(&self, target: Target) -> bool {
+    self.targets.contains(&target)
+  }
+
+  // Generated code refers to the utils module by its last path segment, having imported it in the execution file
+  pub fn utils_module(&self) -> &str {
+    self.runtime.utils.rsplit("::").next().unwrap()
+  }
+
+  pub fn execution_module(&self, section: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let mut parameters: HashMap<String, String> = HashMap::new();
+    parameters.insert("section".to_string(), section.to_string());
+
+    let mut handlebars = Handlebars::new();
+    handlebars.set_strict_mode(true);
+    Ok(handlebars.render_template(&self.naming.execution_module, &parameters)?)
+  }
+}
+
+impl Default for Options {
+  fn default() -> Self {
+    config::Config::default().generator_options()
+  }
+}
+
+// Only GET and POST endpoints are generated so far, anything else is skipped
+pub fn supports(verb: &HttpVerb) -> bool {
+  matches!(verb, HttpVerb::GET | HttpVerb::POST)
+}
+
+// The parameters sent as query or body fields, rather than filled in to the URI
+fn request_fields(endpoint: &Endpoint) -> impl Iterator<Item = &Parameter> {
+  endpoint.parameters.iter().filter(|parameter| parameter.location != Location::Uri)
+}
+
+fn has_request_fields(endpoint: &Endpoint) -> bool {
+  request_fields(endpoint).next().is_some()
+}
+
+// E.g. /r/{{subreddit}}/about/{{where}} -> r_subreddit_about_where, /api/me.json -> api_me_json
+fn api_method_name(api: &template_uri::TemplateUri) -> String {
+  api
+    .template()
+    .trim_start_matches('/')
+    .trim_end_matches('/')
+    .replace("{", "")
+    .replace("}", "")
+    .replace("/", "_")
+    .replace(".", "_")
+}
+
+// E.g. GET /api/v1/me -> GET_API_V1_ME_SCOPES
+fn scopes_constant_name(endpoint: &Endpoint, api: &template_uri::TemplateUri) -> String {
+  endpoint.verb.to_string() + "_" + &api_method_name(api).to_uppercase() + "_SCOPES"
+}
+
+// E.g. /api/v1/me/prefs -> ApiV1MePrefs, the struct of the parameters the endpoint takes
+fn request_model_name(api: &template_uri::TemplateUri) -> String {
+  api
+    .template()
+    .trim_start_matches('/')
+    .trim_end_matches('/')
+    .replace("{", "")
+    .replace("}", "")
+    .replace("/", "_")
+    .to_class_case()
+}
+
+/*
+ * What the generated code for an endpoint is called when it's reached through one of its URIs, by what it is, e.g.
+ * ("wrapper function", "wrapper_get_wiki_page"). Nothing for endpoints that aren't generated.
+ */
+pub fn derived_names(
+  endpoint: &Endpoint,
+  api: &template_uri::TemplateUri,
+  api_section: &str,
+  options: &Options,
+) -> Result<Vec<(&'static str, String)>, Box<dyn std::error::Error>> {
+  if !supports(&endpoint.verb) {
+    return Ok(Vec::new());
+  }
+
+  let verb = endpoint.verb.to_string().to_lowercase();
+  let api_method_name = api_method_name(api);
+
+  let mut names = vec![
+    ("scopes constant", scopes_constant_name(endpoint, api)),
+    (
+      "execution function",
+      options.execution_module(api_section)? + "::execute_" + &verb + "_" + &api_method_name,
+    ),
+    ("wrapper function", "wrapper_".to_string() + &verb + "_" + &api_method_name),
+  ];
+  if endpoint.verb == HttpVerb::GET && endpoint.listing {
+    names.push(("stream function", "stream_get_".to_string() + &api_method_name));
+  }
+  if has_request_fields(endpoint) {
+    names.push(("request model", request_model_name(api)));
+  }
+
+  Ok(names)
+}
+
+// E.g. "read", "submit"
+fn scope_list(endpoint: &Endpoint) -> String {
+  endpoint
+    .scopes
+    .iter()
+    .map(|scope| "\"".to_string() + scope + "\"")
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/*
+ * The endpoint's description, a table of its parameters, the OAuth scopes it needs and a link back to the docs, as
+ * rustdoc for the generated functions.
+ */
+fn rustdoc(endpoint: &Endpoint) -> String {
+  let mut paragraphs = Vec::new();
+  if !endpoint.description.is_empty() {
+    paragraphs.push(endpoint.description.clone());
+  }
+
+  if !endpoint.parameters.is_empty() {
+    let mut table = vec!["| Parameter | Description |".to_string(), "| --- | --- |".to_string()];
+    for parameter in &endpoint.parameters {
+      table.push("| `".to_string() + &parameter.name + "` | " + &table_cell(&parameter.description) + " |");
+    }
+    paragraphs.push(table.join("\n"));
+  }
+
+  if !endpoint.scopes.is_empty() {
+    let scopes: Vec<String> = endpoint.scopes.iter().map(|scope| "`".to_string() + scope + "`").collect();
+    paragraphs.push("OAuth scopes: ".to_string() + &scopes.join(", "));
+  }
+
+  paragraphs.push(docs_link(&endpoint.anchor));
+
+  doc_comment(&paragraphs.join("\n\n"), "")
+}
+
+fn docs_link(anchor: &str) -> String {
+  "[Reddit API docs](".to_string() + DEFAULT_URL + "#" + anchor + ")"
+}
+
+fn doc_comment(markdown: &str, indent: &str) -> String {
+  let mut doc = String::new();
+  for line in markdown.lines() {
+    if line.is_empty() {
+      doc.push_str(&(indent.to_string() + "///\n"));
+    } else {
+      doc.push_str(&(indent.to_string() + "/// " + line + "\n"));
+    }
+  }
+
+  doc
+}
+
+// Markdown tables only allow a single line per cell, so line breaks become <br>, and | would end the cell early
+fn table_cell(markdown: &str) -> String {
+  let lines: Vec<&str> = markdown.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+  lines.join("<br>").replace("|", "\\|")
+}
+
+pub fn write_get_api(
+  endpoint: &Endpoint,
+  api: &template_uri::TemplateUri,
+  options: &Options,
+  mut file: impl Write,
+) -> Result<(), Box<dyn std::error::Error>> {
+  let api_method_name = api_method_name(api);
 
   let mut handlebars = Handlebars::new();
   handlebars.set_strict_mode(true);
 
   let mut parameters: HashMap<String, String> = HashMap::new();
-  parameters.insert("api_path".to_string(), api.template.clone());
+  parameters.insert("api_path".to_string(), api.template());
   parameters.insert("api_name".to_string(), api_method_name);
+  parameters.insert("scopes_constant".to_string(), scopes_constant_name(endpoint, api));
+  parameters.insert("scope_list".to_string(), scope_list(endpoint));
+  parameters.insert("rustdoc".to_string(), rustdoc(endpoint));
+  parameters.insert("base_url".to_string(), options.base_url.clone());
+  parameters.insert("utils".to_string(), options.utils_module().to_string());
 
-  if !api.parameters.is_empty() {
+  if !api.parameters().is_empty() {
     parameters.insert("parameters".to_string(), "true".to_string());
   }
 
-  if !api.request_fields.is_empty() {
+  // Streams page through listings by sending after and limit, whether or not the docs list them
+  if has_request_fields(endpoint) || endpoint.listing {
     parameters.insert("request_fields".to_string(), "true".to_string());
   }
 
@@ -46,22 +279,20 @@ pub fn write_get_api(api: &template_uri::TemplateUri, mut file: &fs::File) -> Re
 }
 
 pub fn write_post_api(
-  http_verb: &HttpVerb,
+  endpoint: &Endpoint,
   api: &template_uri::TemplateUri,
-  mut file: &fs::File,
+  options: &Options,
+  mut file: impl Write,
 ) -> Result<(), Box<dyn std::error::Error>> {
-  file.write_all(("// API is: '".to_string() + &api.template + "'\n").as_bytes())?;
-
-  let api_method_name = str::replace(
-    &api
-      .template
-      .trim_start_matches('/')
-      .trim_end_matches('/')
-      .replace("{", "")
-      .replace("}", ""),
-    "/",
-    "_",
-  );
+  let http_verb = &endpoint.verb;
+  file.write_all(
+    ("pub const ".to_string() + &scopes_constant_name(endpoint, api) + ": &[&str] = &[" + &scope_list(endpoint) + "];\n\n")
+      .as_bytes(),
+  )?;
+  file.write_all(("// API is: '".to_string() + &api.template() + "'\n").as_bytes())?;
+  file.write_all(rustdoc(endpoint).as_bytes())?;
+
+  let api_method_name = api_method_name(api);
   file.write_all(b"pub async fn ")?;
   file.write_all(("execute_".to_string() + &http_verb.to_string().to_lowercase() + "_").as_bytes())?;
   file.write_all(api_method_name.as_bytes())?;
@@ -69,13 +300,13 @@ pub fn write_post_api(
 
   file.write_all(b"  client: &reqwest::Client,\n")?;
   file.write_all(b"  access_token: String,\n")?;
-  if api.parameters.is_empty() {
+  if api.parameters().is_empty() {
     file.write_all(b"  _parameters: &HashMap<String, String>,\n")?;
   } else {
     file.write_all(b"  parameters: &HashMap<String, String>,\n")?;
   }
 
-  if api.request_fields.is_empty() {
+  if !has_request_fields(endpoint) {
     file.write_all(b"  _request_fields: &serde_json::Value,\n")?;
   } else {
     file.write_all(b"  request_fields: &serde_json::Value,\n")?;
@@ -84,18 +315,19 @@ pub fn write_post_api(
   file.write_all(b") -> std::result::Result<reqwest::Response, reqwest::Error> {\n")?;
 
   // We'll need handlebars or templating
-  if !api.parameters.is_empty() {
+  if !api.parameters().is_empty() {
     file.write_all(b"  let mut handlebars = Handlebars::new();\n")?;
     file.write_all(b"  handlebars.set_strict_mode(true);\n")?;
   }
 
   file.write_all(b"  client\n")?;
-  if api.parameters.is_empty() {
+  if api.parameters().is_empty() {
     file.write_all(
       ("    .".to_string()
         + &http_verb.to_string().to_lowercase()
-        + "(\"https://oauth.reddit.com"
-        + &api.template
+        + "(\""
+        + &options.base_url
+        + &api.template()
         + "\")\n")
         .as_bytes(),
     )?;
@@ -103,17 +335,17 @@ pub fn write_post_api(
     file.write_all(
       ("    .".to_string()
         + &http_verb.to_string().to_lowercase()
-        + "(&(\"https://oauth.reddit.com\".to_string() + &handlebars.render_template(\""
-        + &api.template
+        + "(&(\""
+        + &options.base_url
+        + "\".to_string() + &handlebars.render_template(\""
+        + &api.template()
         + "\", &parameters).unwrap()))\n")
         .as_bytes(),
     )?;
   }
   match http_verb {
-    HttpVerb::POST => {
-      if !api.request_fields.is_empty() {
-        file.write_all(b"    .json(&request_fields)\n")?;
-      }
+    HttpVerb::POST if has_request_fields(endpoint) => {
+      file.write_all(b"    .json(&request_fields)\n")?;
     }
     HttpVerb::GET => {
       // file.write_all("  utils::execute_get_api("")
@@ -133,34 +365,30 @@ pub fn write_post_api(
 }
 
 pub fn write_get_wrapper(
+  endpoint: &Endpoint,
   api: &template_uri::TemplateUri,
   api_section: &str,
-  mut file: &fs::File,
+  options: &Options,
+  mut file: impl Write,
 ) -> Result<(), Box<dyn std::error::Error>> {
-  let api_method_name = str::replace(
-    &api
-      .template
-      .trim_start_matches('/')
-      .trim_end_matches('/')
-      .replace("{", "")
-      .replace("}", ""),
-    "/",
-    "_",
-  );
+  let api_method_name = api_method_name(api);
 
   let mut handlebars = Handlebars::new();
   handlebars.set_strict_mode(true);
 
   let mut parameters: HashMap<String, String> = HashMap::new();
-  parameters.insert("api_path".to_string(), api.template.clone());
+  parameters.insert("api_path".to_string(), api.template());
   parameters.insert("api_name".to_string(), api_method_name);
-  parameters.insert("api_section".to_string(), api_section.to_string());
+  parameters.insert("rustdoc".to_string(), rustdoc(endpoint));
+  parameters.insert("execution_module".to_string(), options.execution_module(api_section)?);
+  parameters.insert("utils".to_string(), options.utils_module().to_string());
+  parameters.insert("client_configuration".to_string(), options.runtime.client_configuration.clone());
 
-  if !api.parameters.is_empty() {
+  if !api.parameters().is_empty() {
     parameters.insert("uri_parameters".to_string(), "true".to_string());
   }
 
-  if !api.request_fields.is_empty() {
+  if has_request_fields(endpoint) {
     parameters.insert("query_parameters".to_string(), "true".to_string());
   }
 
@@ -173,33 +401,54 @@ pub fn write_get_wrapper(
   Ok(())
 }
 
+// Only for listings: a stream of every item in the listing, fetched a page at a time
+pub fn write_get_stream(
+  endpoint: &Endpoint,
+  api: &template_uri::TemplateUri,
+  api_section: &str,
+  options: &Options,
+  mut file: impl Write,
+) -> Result<(), Box<dyn std::error::Error>> {
+  let api_method_name = api_method_name(api);
+
+  let mut handlebars = Handlebars::new();
+  handlebars.set_strict_mode(true);
+
+  let mut parameters: HashMap<String, String> = HashMap::new();
+  parameters.insert("api_path".to_string(), api.template());
+  parameters.insert("api_name".to_string(), api_method_name);
+  parameters.insert("rustdoc".to_string(), rustdoc(endpoint));
+  parameters.insert("execution_module".to_string(), options.execution_module(api_section)?);
+  parameters.insert("utils".to_string(), options.utils_module().to_string());
+  parameters.insert("client_configuration".to_string(), options.runtime.client_configuration.clone());
+
+  if !api.parameters().is_empty() {
+    parameters.insert("uri_parameters".to_string(), "true".to_string());
+  }
+
+  let bytes = include_bytes!("handlebars/http_get_stream.handlebars");
+  let handlebars_template = str::from_utf8(bytes).unwrap();
+  let handlebars_template = handlebars.render_template(handlebars_template, &parameters)?;
+
+  file.write_all(handlebars_template.as_bytes())?;
+
+  Ok(())
+}
+
 pub fn write_wrapper(
-  http_verb: &HttpVerb,
+  endpoint: &Endpoint,
   api: &template_uri::TemplateUri,
   api_section: &str,
-  mut file: &fs::File,
+  options: &Options,
+  mut file: impl Write,
 ) -> Result<(), Box<dyn std::error::Error>> {
-  let api_method_name = str::replace(
-    &api
-      .template
-      .trim_start_matches('/')
-      .trim_end_matches('/')
-      .replace("{", "")
-      .replace("}", ""),
-    "/",
-    "_",
-  );
-
-  let structure_name = &api
-    .template
-    .trim_start_matches('/')
-    .trim_end_matches('/')
-    .replace("{", "")
-    .replace("}", "")
-    .replace("/", "_")
-    .to_class_case();
+  let http_verb = &endpoint.verb;
+  let api_method_name = api_method_name(api);
+
+  let structure_name = &request_model_name(api);
 
-  file.write_all(("// API is: '".to_string() + &api.template + "'\n").as_bytes())?;
+  file.write_all(("// API is: '".to_string() + &api.template() + "'\n").as_bytes())?;
+  file.write_all(rustdoc(endpoint).as_bytes())?;
 
   file.write_all(b"pub async fn ")?;
   file.write_all(("wrapper_".to_string() + &http_verb.to_string().to_lowercase() + "_").as_bytes())?;
@@ -207,25 +456,22 @@ pub fn write_wrapper(
   file.write_all(b"(\n")?;
 
   file.write_all(b"  client: &reqwest::Client,\n")?;
-  file.write_all(b"  client_configuration: &client::ClientConfiguration,\n")?;
+  file.write_all(("  client_configuration: &".to_string() + &options.runtime.client_configuration + ",\n").as_bytes())?;
   file.write_all(b"  access_token: &mut String,\n")?;
-  if !api.parameters.is_empty() {
-    if api.template == "/hot" {
-      println!("Parameters are: {:?}", api.parameters);
-    }
+  if !api.parameters().is_empty() {
     file.write_all(b"  parameters: &HashMap<String, String>,\n")?;
   }
 
-  if !api.request_fields.is_empty() {
+  if has_request_fields(endpoint) {
     file.write_all(("  request_fields: ".to_string() + structure_name + ",\n").as_bytes())?
   }
   file.write_all(b") -> Result<serde_json::Value, reqwest::Error> {\n")?;
 
-  file.write_all(b"  utils::execute_with_refresh(\n")?;
+  file.write_all(("  ".to_string() + options.utils_module() + "::execute_with_refresh(\n").as_bytes())?;
   file.write_all(b"    &client,\n")?;
   file.write_all(b"    client_configuration,\n")?;
   file.write_all(b"    access_token,\n")?;
-  if api.parameters.is_empty() {
+  if api.parameters().is_empty() {
     file.write_all(b"    &HashMap::new(),\n")?;
   } else {
     file.write_all(b"    parameters,\n")?;
@@ -239,7 +485,7 @@ pub fn write_wrapper(
     }
     _ => println!("{} isn't handled", http_verb),
   }
-  file.write_all(("    ".to_string() + api_section + "_execution::").as_bytes())?;
+  file.write_all(("    ".to_string() + &options.execution_module(api_section)? + "::").as_bytes())?;
   file.write_all(("execute_".to_string() + &http_verb.to_string().to_lowercase() + "_").as_bytes())?;
   file.write_all(api_method_name.as_bytes())?;
   file.write_all(b",\n")?;
@@ -253,104 +499,515 @@ pub fn write_wrapper(
 }
 
 pub fn write_request_model_file(
+  endpoint: &Endpoint,
   api: &template_uri::TemplateUri,
-  mut file: &fs::File,
+  options: &Options,
+  mut file: impl Write,
 ) -> Result<(), Box<dyn std::error::Error>> {
-  if api.request_fields.is_empty() {
+  if !has_request_fields(endpoint) {
     return Ok(());
   }
 
-  let structure_name = &api
-    .template
-    .trim_start_matches('/')
-    .trim_end_matches('/')
-    .replace("{", "")
-    .replace("}", "")
-    .replace("/", "_")
-    .to_class_case();
+  let structure_name = &request_model_name(api);
 
-  file.write_all(("// API is: '".to_string() + &api.template + "'\n").as_bytes())?;
+  file.write_all(("// API is: '".to_string() + &api.template() + "'\n").as_bytes())?;
   file.write_all(b"#[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]\n")?;
   file.write_all(("pub struct ".to_string() + structure_name + " {\n").as_bytes())?;
 
-  for field in api.request_fields.clone() {
-    match field.0.as_str() {
-      "uh / X-Modhash header" => {
-        // We don't need this, as we're using OAuth
-      }
-      "g-recaptcha-response" => {
-        if !field.1.is_empty() {
-          file.write_all(("  // ".to_string() + &field.1 + "\n").as_bytes())?;
-        }
-        file.write_all(("  #[serde(rename = \"g-recaptcha-response\")]\n\n").as_bytes())?;
-        file.write_all(("  pub g_recaptcha_response: String,\n\n").as_bytes())?;
-      }
-      "type" => {
-        if !field.1.is_empty() {
-          file.write_all(("  // ".to_string() + &field.1 + "\n").as_bytes())?;
-        }
-        file.write_all(("  #[serde(rename = \"type\")]\n").as_bytes())?;
-        file.write_all(("  pub r#type: String,\n\n").as_bytes())?;
+  for field in request_fields(endpoint) {
+    if options.fields.skip.contains(&field.name) {
+      continue;
+    }
+
+    for line in field.description.lines() {
+      if line.is_empty() {
+        file.write_all(b"  //\n")?;
+      } else {
+        file.write_all(("  // ".to_string() + line + "\n").as_bytes())?;
       }
-      "mod" => {
-        if !field.1.is_empty() {
-          file.write_all(("  // ".to_string() + &field.1 + "\n").as_bytes())?;
+    }
+
+    match options.fields.rename.get(&field.name) {
+      Some(identifier) => {
+        // serde already strips the r# from raw identifiers, so those don't need renaming back
+        if identifier.trim_start_matches("r#") != field.name {
+          file.write_all(("  #[serde(rename = \"".to_string() + &field.name + "\")]\n").as_bytes())?;
         }
-        file.write_all(("  #[serde(rename = \"mod\")]\n").as_bytes())?;
-        file.write_all(("  pub r#mod: String,\n\n").as_bytes())?;
+        file.write_all(("  pub ".to_string() + identifier + ": String,\n\n").as_bytes())?;
       }
-      "('user',)" => {
-        if !field.1.is_empty() {
-          file.write_all(("  // ".to_string() + &field.1 + "\n").as_bytes())?;
-        }
-        file.write_all(("  // This isn't right, it's supposed to be a username as the JSON property\n").as_bytes())?;
-        file.write_all(("  pub user: String,\n\n").as_bytes())?;
+      None => {
+        file.write_all(("  pub ".to_string() + &field.name + ": String,\n\n").as_bytes())?;
       }
-      _ => {
-        if !field.1.is_empty() {
-          file.write_all(("  // ".to_string() + &field.1 + "\n").as_bytes())?;
+    }
+  }
+  file.write_all(b"}\n\n")?;
+
+  Ok(())
+}
+
+/*
+ * An enum of the kinds of thing from the overview's table of fullname prefixes, which can be parsed from a fullname or
+ * from the kind field of the API's responses.
+ */
+pub fn write_thing_kind(overview: &Overview, mut file: impl Write) -> Result<(), Box<dyn std::error::Error>> {
+  let variants: Vec<(String, &str)> = overview
+    .thing_kinds
+    .iter()
+    .map(|kind| (kind.name.to_class_case(), kind.prefix.as_str()))
+    .collect();
+
+  let mut doc = Vec::new();
+  if !overview.fullnames.is_empty() {
+    doc.push(overview.fullnames.clone());
+  }
+  doc.push(docs_link("fullnames"));
+  file.write_all(doc_comment(&doc.join("\n\n"), "").as_bytes())?;
+  file.write_all(b"#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\n")?;
+  file.write_all(b"pub enum ThingKind {\n")?;
+  for (variant, prefix) in &variants {
+    file.write_all(("  /// `".to_string() + prefix + "`\n").as_bytes())?;
+    file.write_all(("  ".to_string() + variant + ",\n").as_bytes())?;
+  }
+  file.write_all(b"}\n\n")?;
+
+  file.write_all(b"impl ThingKind {\n")?;
+  file.write_all(("  pub const ALL: [ThingKind; ".to_string() + &variants.len().to_string() + "] = [\n").as_bytes())?;
+  for (variant, _) in &variants {
+    file.write_all(("    ThingKind::".to_string() + variant + ",\n").as_bytes())?;
+  }
+  file.write_all(b"  ];\n\n")?;
+
+  file.write_all(b"  /// The prefix of fullnames of things of this kind, e.g. `t1_`\n")?;
+  file.write_all(b"  pub fn prefix(&self) -> &'static str {\n")?;
+  file.write_all(b"    match self {\n")?;
+  for (variant, prefix) in &variants {
+    file.write_all(("      ThingKind::".to_string() + variant + " => \"" + prefix + "\",\n").as_bytes())?;
+  }
+  file.write_all(b"    }\n")?;
+  file.write_all(b"  }\n\n")?;
+
+  file.write_all(b"  /// Splits a fullname such as `t3_15bfi0` into the kind of thing it refers to and the thing's ID\n")?;
+  file.write_all(b"  pub fn from_fullname(fullname: &str) -> Option<(ThingKind, &str)> {\n")?;
+  file.write_all(b"    ThingKind::ALL\n")?;
+  file.write_all(b"      .iter()\n")?;
+  file.write_all(b"      .find_map(|kind| fullname.strip_prefix(kind.prefix()).map(|id| (*kind, id)))\n")?;
+  file.write_all(b"  }\n")?;
+  file.write_all(b"}\n\n")?;
+
+  file.write_all(b"/// Parses a kind as it appears in the `kind` field of responses, e.g. `t3`, or as a prefix, e.g. `t3_`\n")?;
+  file.write_all(b"impl std::str::FromStr for ThingKind {\n")?;
+  file.write_all(b"  type Err = String;\n\n")?;
+  file.write_all(b"  fn from_str(kind: &str) -> Result<Self, Self::Err> {\n")?;
+  file.write_all(b"    ThingKind::ALL\n")?;
+  file.write_all(b"      .iter()\n")?;
+  file.write_all(b"      .copied()\n")?;
+  file.write_all(b"      .find(|thing_kind| thing_kind.prefix().trim_end_matches('_') == kind.trim_end_matches('_'))\n")?;
+  file.write_all(b"      .ok_or_else(|| format!(\"Unknown kind of thing '{}'\", kind))\n")?;
+  file.write_all(b"  }\n")?;
+  file.write_all(b"}\n\n")?;
+
+  file.write_all(b"/// Formats the kind as it appears in the `kind` field of responses, e.g. `t3`\n")?;
+  file.write_all(b"impl std::fmt::Display for ThingKind {\n")?;
+  file.write_all(b"  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n")?;
+  file.write_all(b"    write!(f, \"{}\", self.prefix().trim_end_matches('_'))\n")?;
+  file.write_all(b"  }\n")?;
+  file.write_all(b"}\n")?;
+
+  Ok(())
+}
+
+// The parameters the overview says every listing takes, all optional
+pub fn write_listing_parameters(
+  overview: &Overview,
+  options: &Options,
+  mut file: impl Write,
+) -> Result<(), Box<dyn std::error::Error>> {
+  let mut doc = Vec::new();
+  if !overview.listings.is_empty() {
+    doc.push(overview.listings.clone());
+  }
+  doc.push(docs_link("listings"));
+  file.write_all(doc_comment(&doc.join("\n\n"), "").as_bytes())?;
+  file.write_all(b"#[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]\n")?;
+  file.write_all(b"pub struct ListingParameters {\n")?;
+
+  for (i, parameter) in overview.listing_parameters.iter().enumerate() {
+    if i > 0 {
+      file.write_all(b"\n")?;
+    }
+    file.write_all(doc_comment(&parameter.description, "  ").as_bytes())?;
+
+    let identifier = match options.fields.rename.get(&parameter.name) {
+      Some(identifier) => {
+        if identifier.trim_start_matches("r#") != parameter.name {
+          file.write_all(("  #[serde(rename = \"".to_string() + &parameter.name + "\")]\n").as_bytes())?;
         }
-        file.write_all(("  pub ".to_string() + &field.0 + ": String,\n\n").as_bytes())?;
+        identifier
       }
+      None => &parameter.name,
+    };
+    file.write_all(b"  #[serde(skip_serializing_if = \"Option::is_none\")]\n")?;
+    file.write_all(("  pub ".to_string() + identifier + ": Option<String>,\n").as_bytes())?;
+  }
+  file.write_all(b"}\n")?;
+
+  Ok(())
+}
+
+/*
+ * An enum of the OAuth scopes from the scope pages, each knowing which endpoints it gives access to, so a bot can work
+ * out the scopes to ask for from the endpoints it calls.
+ */
+pub fn write_scope(scopes: &[Scope], mut file: impl Write) -> Result<(), Box<dyn std::error::Error>> {
+  let variants: Vec<(String, &Scope)> = scopes.iter().map(|scope| (scope.name.to_class_case(), scope)).collect();
+
+  file.write_all(b"/// An OAuth scope, which an access token has to be granted for the endpoints it covers\n")?;
+  file.write_all(b"#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\n")?;
+  file.write_all(b"pub enum Scope {\n")?;
+  for (variant, scope) in &variants {
+    if !scope.description.is_empty() {
+      file.write_all(doc_comment(&scope.description, "  ").as_bytes())?;
     }
+    file.write_all(("  ".to_string() + variant + ",\n").as_bytes())?;
+  }
+  file.write_all(b"}\n\n")?;
+
+  file.write_all(b"impl Scope {\n")?;
+  file.write_all(("  pub const ALL: [Scope; ".to_string() + &variants.len().to_string() + "] = [\n").as_bytes())?;
+  for (variant, _) in &variants {
+    file.write_all(("    Scope::".to_string() + variant + ",\n").as_bytes())?;
+  }
+  file.write_all(b"  ];\n\n")?;
+
+  file.write_all(b"  /// The scope as it's requested when authorizing, e.g. `read`\n")?;
+  file.write_all(b"  pub fn name(&self) -> &'static str {\n")?;
+  file.write_all(b"    match self {\n")?;
+  for (variant, scope) in &variants {
+    file.write_all(format!("      Scope::{} => {:?},\n", variant, scope.name).as_bytes())?;
   }
+  file.write_all(b"    }\n")?;
+  file.write_all(b"  }\n\n")?;
+
+  file.write_all(b"  pub fn description(&self) -> &'static str {\n")?;
+  file.write_all(b"    match self {\n")?;
+  for (variant, scope) in &variants {
+    file.write_all(format!("      Scope::{} => {:?},\n", variant, scope.description).as_bytes())?;
+  }
+  file.write_all(b"    }\n")?;
+  file.write_all(b"  }\n\n")?;
+
+  file.write_all(b"  /// The anchors in the API docs of the endpoints the scope gives access to, e.g. `GET_api_v1_me`\n")?;
+  file.write_all(b"  pub fn endpoints(&self) -> &'static [&'static str] {\n")?;
+  file.write_all(b"    match self {\n")?;
+  for (variant, scope) in &variants {
+    let endpoints: Vec<String> = scope.endpoints.iter().map(|anchor| format!("{:?}", anchor)).collect();
+    file.write_all(format!("      Scope::{} => &[{}],\n", variant, endpoints.join(", ")).as_bytes())?;
+  }
+  file.write_all(b"    }\n")?;
+  file.write_all(b"  }\n")?;
   file.write_all(b"}\n\n")?;
 
+  file.write_all(b"impl std::str::FromStr for Scope {\n")?;
+  file.write_all(b"  type Err = String;\n\n")?;
+  file.write_all(b"  fn from_str(name: &str) -> Result<Self, Self::Err> {\n")?;
+  file.write_all(b"    Scope::ALL\n")?;
+  file.write_all(b"      .iter()\n")?;
+  file.write_all(b"      .copied()\n")?;
+  file.write_all(b"      .find(|scope| scope.name() == name)\n")?;
+  file.write_all(b"      .ok_or_else(|| format!(\"Unknown scope '{}'\", name))\n")?;
+  file.write_all(b"  }\n")?;
+  file.write_all(b"}\n\n")?;
+
+  file.write_all(b"impl std::fmt::Display for Scope {\n")?;
+  file.write_all(b"  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {\n")?;
+  file.write_all(b"    write!(f, \"{}\", self.name())\n")?;
+  file.write_all(b"  }\n")?;
+  file.write_all(b"}\n")?;
+
   Ok(())
 }
 
-pub async fn generate() -> Result<(), Box<dyn std::error::Error>> {
-  fs::create_dir_all("target/output/execution")?;
-  fs::create_dir_all("target/output/wrapper")?;
-  fs::create_dir_all("target/output/request_models")?;
+/*
+ * Generates every file for the catalog in memory. Nothing is written until GeneratedFiles::write is called, so the
+ * files can also be compared against what's already on disk, or used some other way entirely.
+ */
+pub fn generate(catalog: &ApiCatalog, options: &Options) -> Result<GeneratedFiles, Box<dyn std::error::Error>> {
+  let mut generated = GeneratedFiles::default();
+
+  for section in &catalog.sections {
+    let filename = section.file_name();
+
+    let mut execution_file = Vec::new();
+    execution_file.write_all(("use ".to_string() + &options.runtime.utils + ";\n").as_bytes())?;
+    execution_file.write_all(("use std::collections::HashMap;\n").as_bytes())?;
+    execution_file.write_all(("\n").as_bytes())?;
+
+    let mut wrapper_file = Vec::new();
+
+    let mut request_model_file = Vec::new();
+    request_model_file.write_all(("use serde::Serialize;\n\n").as_bytes())?;
+
+    for endpoint in &section.endpoints {
+      for uri in &endpoint.uris {
+        match endpoint.verb {
+          HttpVerb::GET => {
+            write_get_api(endpoint, uri, options, &mut execution_file)?;
+            write_get_wrapper(endpoint, uri, &filename, options, &mut wrapper_file)?;
+            if endpoint.listing {
+              write_get_stream(endpoint, uri, &filename, options, &mut wrapper_file)?;
+            }
+          }
+          HttpVerb::POST => {
+            write_post_api(endpoint, uri, options, &mut execution_file)?;
+            write_wrapper(endpoint, uri, &filename, options, &mut wrapper_file)?;
+          }
+          // Reported by validation, so only worth mentioning in detail
+          _ => {
+            debug!("        Support for {} not yet implemented", endpoint.verb);
+            continue;
+          }
+        }
 
-  Ok(())
+        write_request_model_file(endpoint, uri, options, &mut request_model_file)?;
+      }
+    }
+
+    for (target, contents) in [
+      (Target::Execution, execution_file),
+      (Target::Wrapper, wrapper_file),
+      (Target::RequestModels, request_model_file),
+    ] {
+      if options.emits(target) {
+        let path = Path::new(target.directory()).join(filename.clone() + ".rs");
+        generated.files.insert(path, String::from_utf8(contents)?);
+      }
+    }
+  }
+
+  if options.emits(Target::Overview) {
+    if !catalog.overview.thing_kinds.is_empty() {
+      let mut thing_kind_file = Vec::new();
+      write_thing_kind(&catalog.overview, &mut thing_kind_file)?;
+      let path = Path::new(Target::Overview.directory()).join("thing_kind.rs");
+      generated.files.insert(path, String::from_utf8(thing_kind_file)?);
+    }
+
+    if !catalog.overview.listing_parameters.is_empty() {
+      let mut listing_parameters_file = Vec::new();
+      write_listing_parameters(&catalog.overview, options, &mut listing_parameters_file)?;
+      let path = Path::new(Target::Overview.directory()).join("listing_parameters.rs");
+      generated.files.insert(path, String::from_utf8(listing_parameters_file)?);
+    }
+
+    if !catalog.scopes.is_empty() {
+      let mut scope_file = Vec::new();
+      write_scope(&catalog.scopes, &mut scope_file)?;
+      let path = Path::new(Target::Overview.directory()).join("scope.rs");
+      generated.files.insert(path, String::from_utf8(scope_file)?);
+    }
+  }
+
+  Ok(generated)
+}
+
+#[derive(Debug, Default)]
+pub struct GeneratedFiles {
+  // Contents by path relative to the output root, e.g. execution/wiki.rs
+  pub files: BTreeMap<PathBuf, String>,
 }
 
-pub async fn create_execution_file(filename: &str) -> std::io::Result<fs::File> {
-  let path = &("./target/output/execution/".to_string() + filename + ".rs");
-  let path = Path::new(path);
-  let mut file = fs::File::create(path)?;
+impl GeneratedFiles {
+  pub fn write(&self, output_dir: &Path) -> std::io::Result<()> {
+    for (path, contents) in &self.files {
+      let path = output_dir.join(path);
+      if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+      }
+      fs::write(path, contents)?;
+    }
+
+    Ok(())
+  }
 
-  file.write_all(("use crate::api::utils;\n").as_bytes())?;
-  file.write_all(("use std::collections::HashMap;\n").as_bytes())?;
-  file.write_all(("\n").as_bytes())?;
+  /*
+   * Files (relative to the output root) that are missing from output_dir, differ from what would be generated, or
+   * wouldn't be generated at all. Only the directories being generated into are looked at, so anything else under the
+   * output root is left alone.
+   */
+  pub fn stale_files(&self, output_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let generated_directories: BTreeSet<_> = self.files.keys().filter_map(|file| file.iter().next()).collect();
+
+    let mut existing_files = BTreeSet::new();
+    for directory in &generated_directories {
+      if output_dir.join(directory).is_dir() {
+        list_files(output_dir, Path::new(directory), &mut existing_files)?;
+      }
+    }
+
+    let mut stale = Vec::new();
+    for file in existing_files.iter().chain(self.files.keys()).collect::<BTreeSet<_>>() {
+      let unchanged = match self.files.get(file) {
+        Some(contents) => existing_files.contains(file) && fs::read(output_dir.join(file))? == contents.as_bytes(),
+        None => false,
+      };
+      if !unchanged {
+        stale.push(file.clone());
+      }
+    }
 
-  Ok(file)
+    Ok(stale)
+  }
 }
 
-pub async fn create_wrapper_file(filename: &str) -> std::io::Result<fs::File> {
-  let path = &("./target/output/wrapper/".to_string() + filename + ".rs");
-  let path = Path::new(path);
-  let file = fs::File::create(path)?;
-  Ok(file)
+fn list_files(root: &Path, relative: &Path, files: &mut BTreeSet<PathBuf>) -> std::io::Result<()> {
+  for entry in fs::read_dir(root.join(relative))? {
+    let entry = entry?;
+    let relative = relative.join(entry.file_name());
+    if entry.file_type()?.is_dir() {
+      list_files(root, &relative, files)?;
+    } else {
+      files.insert(relative);
+    }
+  }
+
+  Ok(())
 }
 
-pub async fn create_request_model_file(filename: &str) -> std::io::Result<fs::File> {
-  let path = &("./target/output/request_models/".to_string() + filename + ".rs");
-  let path = Path::new(path);
-  let mut file = fs::File::create(path)?;
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::catalog::{ListingParameter, ThingKind};
+
+  fn generate_fixture() -> GeneratedFiles {
+    let html = fs::read_to_string("fixtures/dev_api.html").unwrap();
+    generate(&crate::scrape_document(&html).unwrap(), &Options::default()).unwrap()
+  }
 
-  file.write_all(("use serde::Serialize;\n\n").as_bytes())?;
-  Ok(file)
+  #[test]
+  fn generating_twice_produces_the_same_files() {
+    let first = generate_fixture();
+    assert!(!first.files.is_empty());
+    assert_eq!(first.files, generate_fixture().files);
+  }
+
+  #[test]
+  fn uri_parameters_are_not_request_fields() {
+    let generated = generate_fixture();
+    let request_models = &generated.files[Path::new("request_models/listings.rs")];
+    let execution = &generated.files[Path::new("execution/listings.rs")];
+
+    // GET /by_id/{{names}} has nothing but its URI parameter
+    assert!(!request_models.contains("pub struct ByIdName"));
+    assert!(execution.contains(
+      "pub async fn execute_get_by_id_names(\n  client: &reqwest::Client,\n  access_token: String,  parameters: \
+       &HashMap<String, String>,  _request_fields: &serde_json::Value,"
+    ));
+
+    // GET /comments/{{article}} sends everything else in its query string
+    assert!(!request_models.contains("pub article: String"));
+    assert!(request_models.contains("pub depth: String"));
+  }
+
+  #[test]
+  fn listings_send_their_request_fields_even_without_a_parameter_table() {
+    let endpoint = Endpoint {
+      anchor: "GET_best".to_string(),
+      verb: HttpVerb::GET,
+      uris: vec![template_uri::TemplateUri {
+        segments: vec![template_uri::Segment::Literal {
+          text: "/best".to_string(),
+        }],
+      }],
+      parameters: Vec::new(),
+      description: String::new(),
+      scopes: Vec::new(),
+      listing: true,
+    };
+
+    let mut execution = Vec::new();
+    write_get_api(&endpoint, &endpoint.uris[0], &Options::default(), &mut execution).unwrap();
+    let execution = String::from_utf8(execution).unwrap();
+    assert!(execution.contains("  request_fields: &serde_json::Value,"));
+    assert!(execution.contains("for request_field in request_fields.as_object().unwrap()"));
+  }
+
+  fn overview() -> Overview {
+    Overview {
+      fullnames: "A fullname is a thing's type and ID.".to_string(),
+      thing_kinds: vec![
+        ThingKind {
+          prefix: "t1_".to_string(),
+          name: "Comment".to_string(),
+        },
+        ThingKind {
+          prefix: "t5_".to_string(),
+          name: "Subreddit".to_string(),
+        },
+      ],
+      listings: "Listings are paged.".to_string(),
+      listing_parameters: vec![
+        ListingParameter {
+          name: "after".to_string(),
+          description: "the [fullname](https://www.reddit.com/dev/api#fullnames) to start after".to_string(),
+        },
+        ListingParameter {
+          name: "type".to_string(),
+          description: "what to list".to_string(),
+        },
+        ListingParameter {
+          name: "sr-detail".to_string(),
+          description: "expand subreddits".to_string(),
+        },
+      ],
+      modhashes: String::new(),
+    }
+  }
+
+  #[test]
+  fn thing_kinds_are_an_enum_of_the_fullname_prefixes() {
+    let mut file = Vec::new();
+    write_thing_kind(&overview(), &mut file).unwrap();
+    let file = String::from_utf8(file).unwrap();
+
+    assert!(file.starts_with(
+      "/// A fullname is a thing's type and ID.\n///\n/// [Reddit API docs](https://www.reddit.com/dev/api#fullnames)\n"
+    ));
+    assert!(file.contains("pub enum ThingKind {\n  /// `t1_`\n  Comment,\n  /// `t5_`\n  Subreddit,\n}\n"));
+    assert!(file.contains("[ThingKind; 2] = [\n    ThingKind::Comment,\n    ThingKind::Subreddit,\n  ];"));
+    assert!(file.contains("      ThingKind::Comment => \"t1_\",\n      ThingKind::Subreddit => \"t5_\",\n"));
+    assert!(file.contains("impl std::str::FromStr for ThingKind {"));
+    assert!(file.contains("impl std::fmt::Display for ThingKind {"));
+    syn::parse_file(&file).unwrap();
+  }
+
+  #[test]
+  fn listing_parameters_are_optional_fields_named_like_request_fields() {
+    let mut options = Options::default();
+    options.fields.rename.insert("sr-detail".to_string(), "sr_detail".to_string());
+    let mut file = Vec::new();
+    write_listing_parameters(&overview(), &options, &mut file).unwrap();
+
+    assert_eq!(
+      String::from_utf8(file).unwrap(),
+      r#"/// Listings are paged.
+///
+/// [Reddit API docs](https://www.reddit.com/dev/api#listings)
+#[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ListingParameters {
+  /// the [fullname](https://www.reddit.com/dev/api#fullnames) to start after
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub after: Option<String>,
+
+  /// what to list
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub r#type: Option<String>,
+
+  /// expand subreddits
+  #[serde(rename = "sr-detail")]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub sr_detail: Option<String>,
+}
+"#
+    );
+  }
 }
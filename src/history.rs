@@ -0,0 +1,371 @@
+use crate::catalog::{ApiCatalog, ApiSection, Endpoint};
+use crate::diff::{self, CatalogDiff, Change};
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/*
+ * When each endpoint appeared in, changed in and disappeared from the docs, over a series of snapshots in
+ * chronological order. Built by diffing each snapshot against the one before it, except that an endpoint coming back
+ * after disappearing is compared to how it was when it was last seen.
+ */
+#[derive(Debug, Serialize)]
+pub struct History {
+  pub snapshots: Vec<String>,
+  pub endpoints: Vec<EndpointHistory>,
+  // Snapshots that couldn't be scraped, and so aren't in the history at all
+  pub skipped: Vec<SkippedSnapshot>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SkippedSnapshot {
+  pub path: String,
+  pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EndpointHistory {
+  pub anchor: String,
+  // As of the last snapshot the endpoint was in
+  pub section: String,
+  pub first_seen: String,
+  pub last_seen: String,
+  // Whether the endpoint is in the most recent snapshot
+  pub current: bool,
+  pub events: Vec<Event>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+  Appeared { snapshot: String },
+  Changed { snapshot: String, changes: Vec<Change> },
+  Disappeared { snapshot: String },
+}
+
+impl History {
+  // Snapshots are (label, catalog) pairs, oldest first; labels are usually dates
+  pub fn build(snapshots: &[(String, ApiCatalog)]) -> History {
+    let mut endpoints: BTreeMap<String, EndpointHistory> = BTreeMap::new();
+    let empty = ApiCatalog::default();
+    // Each endpoint as it was in the last snapshot it was in
+    let mut last_versions: BTreeMap<&str, (&ApiSection, &Endpoint)> = BTreeMap::new();
+
+    let mut previous = &empty;
+    for (label, catalog) in snapshots {
+      let diff = CatalogDiff::between(previous, catalog);
+
+      for added in diff.added {
+        let history = endpoints.entry(added.anchor.clone()).or_insert_with(|| EndpointHistory {
+          anchor: added.anchor.clone(),
+          section: added.section.clone(),
+          first_seen: label.clone(),
+          last_seen: label.clone(),
+          current: false,
+          events: Vec::new(),
+        });
+        history.events.push(Event::Appeared { snapshot: label.clone() });
+
+        if let (Some((old_section, old_endpoint)), Some((new_section, new_endpoint))) = (
+          last_versions.get(added.anchor.as_str()),
+          catalog.endpoints().find(|(_, endpoint)| endpoint.anchor == added.anchor),
+        ) {
+          let changes = diff::endpoint_changes(old_section, old_endpoint, new_section, new_endpoint);
+          if !changes.is_empty() {
+            history.events.push(Event::Changed {
+              snapshot: label.clone(),
+              changes,
+            });
+          }
+        }
+      }
+
+      for removed in diff.removed {
+        if let Some(history) = endpoints.get_mut(&removed.anchor) {
+          history.events.push(Event::Disappeared { snapshot: label.clone() });
+        }
+      }
+
+      for changed in diff.changed {
+        if let Some(history) = endpoints.get_mut(&changed.anchor) {
+          history.events.push(Event::Changed {
+            snapshot: label.clone(),
+            changes: changed.changes,
+          });
+        }
+      }
+
+      for (section, endpoint) in catalog.endpoints() {
+        last_versions.insert(&endpoint.anchor, (section, endpoint));
+        if let Some(history) = endpoints.get_mut(&endpoint.anchor) {
+          history.section = section.name.clone();
+          history.last_seen = label.clone();
+        }
+      }
+
+      previous = catalog;
+    }
+
+    if let Some((_, latest)) = snapshots.last() {
+      for (_, endpoint) in latest.endpoints() {
+        if let Some(history) = endpoints.get_mut(&endpoint.anchor) {
+          history.current = true;
+        }
+      }
+    }
+
+    History {
+      snapshots: snapshots.iter().map(|(label, _)| label.clone()).collect(),
+      endpoints: endpoints.into_values().collect(),
+      skipped: Vec::new(),
+    }
+  }
+
+  pub fn to_markdown(&self) -> Result<String, std::fmt::Error> {
+    let mut markdown = String::new();
+
+    writeln!(markdown, "# Endpoint history")?;
+    writeln!(markdown)?;
+    match (self.snapshots.first(), self.snapshots.last()) {
+      (Some(first), Some(last)) => writeln!(
+        markdown,
+        "{} snapshot(s) from {} to {}, {} endpoint(s).",
+        self.snapshots.len(),
+        first,
+        last,
+        self.endpoints.len()
+      )?,
+      _ => writeln!(markdown, "No snapshots.")?,
+    }
+    writeln!(markdown)?;
+
+    if !self.skipped.is_empty() {
+      writeln!(markdown, "Skipped {} snapshot(s) that couldn't be scraped:", self.skipped.len())?;
+      writeln!(markdown)?;
+      for skipped in &self.skipped {
+        writeln!(markdown, "- `{}`: {}", skipped.path, skipped.reason)?;
+      }
+      writeln!(markdown)?;
+    }
+
+    writeln!(markdown, "| Endpoint | Section | First seen | Last seen | Status |")?;
+    writeln!(markdown, "| --- | --- | --- | --- | --- |")?;
+    for endpoint in &self.endpoints {
+      writeln!(
+        markdown,
+        "| `{}` | {} | {} | {} | {} |",
+        endpoint.anchor,
+        endpoint.section,
+        endpoint.first_seen,
+        endpoint.last_seen,
+        if endpoint.current { "current" } else { "removed" }
+      )?;
+    }
+
+    // Only endpoints that did more than appear once get a timeline of their own
+    for endpoint in self.endpoints.iter().filter(|endpoint| endpoint.events.len() > 1) {
+      writeln!(markdown)?;
+      writeln!(markdown, "## `{}`", endpoint.anchor)?;
+      writeln!(markdown)?;
+      for event in &endpoint.events {
+        match event {
+          Event::Appeared { snapshot } => writeln!(markdown, "- {}: appeared", snapshot)?,
+          Event::Disappeared { snapshot } => writeln!(markdown, "- {}: disappeared", snapshot)?,
+          Event::Changed { snapshot, changes } => {
+            writeln!(markdown, "- {}:", snapshot)?;
+            for change in changes {
+              writeln!(markdown, "  - {}", change)?;
+            }
+          }
+        }
+      }
+    }
+
+    Ok(markdown)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::http_verb::HttpVerb;
+
+  // Sections of (anchor, description) pairs; the description stands in for anything about an endpoint changing
+  fn snapshot(label: &str, sections: &[(&str, &[(&str, &str)])]) -> (String, ApiCatalog) {
+    let sections = sections
+      .iter()
+      .map(|(name, endpoints)| ApiSection {
+        name: name.to_string(),
+        endpoints: endpoints
+          .iter()
+          .map(|(anchor, description)| Endpoint {
+            anchor: anchor.to_string(),
+            verb: HttpVerb::GET,
+            uris: Vec::new(),
+            parameters: Vec::new(),
+            description: description.to_string(),
+            scopes: Vec::new(),
+            listing: false,
+          })
+          .collect(),
+      })
+      .collect();
+
+    (
+      label.to_string(),
+      ApiCatalog {
+        sections,
+        ..ApiCatalog::default()
+      },
+    )
+  }
+
+  fn endpoint<'a>(history: &'a History, anchor: &str) -> &'a EndpointHistory {
+    history.endpoints.iter().find(|endpoint| endpoint.anchor == anchor).unwrap()
+  }
+
+  fn appeared(snapshot: &str) -> Event {
+    Event::Appeared {
+      snapshot: snapshot.to_string(),
+    }
+  }
+
+  fn disappeared(snapshot: &str) -> Event {
+    Event::Disappeared {
+      snapshot: snapshot.to_string(),
+    }
+  }
+
+  fn changed(snapshot: &str, change: Change) -> Event {
+    Event::Changed {
+      snapshot: snapshot.to_string(),
+      changes: vec![change],
+    }
+  }
+
+  fn description_changed(old: &str, new: &str) -> Change {
+    Change::DescriptionChanged {
+      old: old.to_string(),
+      new: new.to_string(),
+    }
+  }
+
+  fn history() -> History {
+    History::build(&[
+      snapshot(
+        "2018",
+        &[("account", &[("me", "identity"), ("karma", "karma"), ("trophies", "trophies")])],
+      ),
+      snapshot(
+        "2019",
+        &[("account", &[("me", "identity"), ("karma", "karma"), ("trophies", "trophies")])],
+      ),
+      snapshot("2020", &[("account", &[("me", "the identity")])]),
+      snapshot(
+        "2021",
+        &[("account", &[("karma", "karma by subreddit")]), ("users", &[("me", "the identity")])],
+      ),
+    ])
+  }
+
+  #[test]
+  fn endpoints_are_seen_from_when_they_appear_to_the_last_snapshot_they_are_in() {
+    let history = history();
+    assert_eq!(history.snapshots, ["2018", "2019", "2020", "2021"]);
+
+    let trophies = endpoint(&history, "trophies");
+    assert_eq!((trophies.first_seen.as_str(), trophies.last_seen.as_str()), ("2018", "2019"));
+    assert!(!trophies.current);
+    assert_eq!(trophies.events, [appeared("2018"), disappeared("2020")]);
+  }
+
+  #[test]
+  fn changes_are_in_the_snapshot_they_are_first_seen_in() {
+    let history = history();
+
+    let me = endpoint(&history, "me");
+    assert_eq!((me.first_seen.as_str(), me.last_seen.as_str()), ("2018", "2021"));
+    assert!(me.current);
+    assert_eq!(me.section, "users");
+    assert_eq!(
+      me.events,
+      [
+        appeared("2018"),
+        changed("2020", description_changed("identity", "the identity")),
+        changed(
+          "2021",
+          Change::Moved {
+            from: "account".to_string(),
+            to: "users".to_string(),
+          }
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn an_endpoint_that_comes_back_is_compared_to_when_it_was_last_seen() {
+    let history = history();
+
+    let karma = endpoint(&history, "karma");
+    assert_eq!((karma.first_seen.as_str(), karma.last_seen.as_str()), ("2018", "2021"));
+    assert!(karma.current);
+    assert_eq!(
+      karma.events,
+      [
+        appeared("2018"),
+        disappeared("2020"),
+        appeared("2021"),
+        changed("2021", description_changed("karma", "karma by subreddit")),
+      ]
+    );
+  }
+
+  #[test]
+  fn markdown_lists_every_endpoint_the_skipped_snapshots_and_timelines() {
+    let mut history = History::build(&[
+      snapshot("2018", &[("account", &[("me", "identity"), ("trophies", "trophies")])]),
+      snapshot("2020", &[("account", &[("me", "the identity")])]),
+    ]);
+    history.skipped.push(SkippedSnapshot {
+      path: "snapshots/2019.html".to_string(),
+      reason: "No layout recognizes the document".to_string(),
+    });
+
+    assert_eq!(
+      history.to_markdown().unwrap(),
+      "# Endpoint history
+
+2 snapshot(s) from 2018 to 2020, 2 endpoint(s).
+
+Skipped 1 snapshot(s) that couldn't be scraped:
+
+- `snapshots/2019.html`: No layout recognizes the document
+
+| Endpoint | Section | First seen | Last seen | Status |
+| --- | --- | --- | --- | --- |
+| `me` | account | 2018 | 2020 | current |
+| `trophies` | account | 2018 | 2018 | removed |
+
+## `me`
+
+- 2018: appeared
+- 2020:
+  - ~ description: \"identity\" -> \"the identity\"
+
+## `trophies`
+
+- 2018: appeared
+- 2020: disappeared
+"
+    );
+  }
+
+  #[test]
+  fn no_snapshots() {
+    let history = History::build(&[]);
+    assert!(history.endpoints.is_empty());
+    assert!(history.to_markdown().unwrap().contains("No snapshots."));
+  }
+}
@@ -1,6 +1,10 @@
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug)]
+// Named after the verbs as they appear in the docs' anchors (e.g. #GET_wiki_{page})
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum HttpVerb {
   GET,
   HEAD,
@@ -13,9 +17,11 @@ pub enum HttpVerb {
   PATCH,
 }
 
-impl HttpVerb {
-  pub fn from(string: &str) -> HttpVerb {
-    match string {
+impl FromStr for HttpVerb {
+  type Err = String;
+
+  fn from_str(string: &str) -> Result<Self, Self::Err> {
+    Ok(match string {
       "GET" => HttpVerb::GET,
       "HEAD" => HttpVerb::HEAD,
       "POST" => HttpVerb::POST,
@@ -25,8 +31,8 @@ impl HttpVerb {
       "OPTIONS" => HttpVerb::OPTIONS,
       "CONNECT" => HttpVerb::CONNECT,
       "PATCH" => HttpVerb::PATCH,
-      _ => panic!("No match for {}", string),
-    }
+      _ => return Err(format!("No match for {}", string)),
+    })
   }
 }
 
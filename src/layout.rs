@@ -0,0 +1,796 @@
+use crate::catalog::{ListingParameter, Overview, ThingKind};
+use crate::config;
+use crate::document_index::DocumentIndex;
+use crate::markdown;
+use crate::scrape_error::ScrapeError;
+
+use indexmap::IndexMap;
+use scraper::element_ref::ElementRef;
+use scraper::{Html, Selector};
+use std::sync::OnceLock;
+
+/*
+ * The markup of /dev/api has changed over the years, so archived copies (e.g. from the Wayback Machine) don't all look
+ * like the live page. Each layout knows how to find the same information (sections, endpoints, URIs, parameters) in
+ * one version of the markup, and how to recognize a document that uses it.
+ */
+pub trait Layout {
+  fn name(&self) -> &'static str;
+
+  fn selectors(&self) -> &Selectors;
+
+  fn detect(&self, document: &Html) -> bool;
+
+  // An entry of the table of contents that can't be made sense of is a failure in its place, the rest are still read
+  fn sections<'a>(&self, index: &DocumentIndex<'a>) -> Vec<Result<SidebarSection<'a>, ScrapeError>>;
+
+  // The documented URIs, with parameters as {{name}} and optional parts in [brackets]
+  fn uri_prototypes(&self, details: ElementRef) -> Vec<String>;
+
+  // A row that can't be read is left out, with its failure pushed to `failures`
+  fn parameters(&self, details: ElementRef, failures: &mut Vec<ScrapeError>) -> IndexMap<String, String>;
+
+  // As Markdown, like the parameter descriptions
+  fn description(&self, details: ElementRef) -> String;
+
+  // The OAuth scopes the endpoint needs, from the badges next to its URI
+  fn scopes(&self, details: ElementRef) -> Vec<String>;
+
+  // Whether the endpoint returns a listing, which can be paged through with after/before
+  fn is_listing(&self, details: ElementRef) -> bool;
+
+  fn overview(&self, index: &DocumentIndex) -> Overview;
+
+  // The introduction under a section's heading, as Markdown; on the OAuth scope pages this describes the scope
+  fn section_description(&self, section: ElementRef) -> String;
+}
+
+pub struct SidebarSection<'a> {
+  pub name: String,
+  pub endpoints: Vec<Result<SidebarEndpoint<'a>, ScrapeError>>,
+  // The element of the section itself, if its link leads anywhere
+  pub details: Option<ElementRef<'a>>,
+}
+
+pub struct SidebarEndpoint<'a> {
+  // E.g. GET_wiki_{page}
+  pub anchor: String,
+  // The element documenting the endpoint, if the anchor leads anywhere
+  pub details: Option<ElementRef<'a>>,
+}
+
+// A selector as written, so it can be named when it doesn't match anything
+#[derive(Debug, Clone)]
+pub struct CssSelector {
+  pub text: String,
+  selector: Selector,
+}
+
+impl CssSelector {
+  pub fn parse(text: &str) -> Result<CssSelector, ScrapeError> {
+    Ok(CssSelector {
+      text: text.to_string(),
+      selector: Selector::parse(text).map_err(|_| ScrapeError::InvalidSelector {
+        section: String::new(),
+        anchor: String::new(),
+        selector: text.to_string(),
+      })?,
+    })
+  }
+}
+
+/*
+ * The CSS selectors a layout finds its way around a document with, parsed once for the whole document. When the markup
+ * changes, any of them can be replaced under [scraping.selectors.<layout>] in the config.
+ */
+#[derive(Debug, Clone)]
+pub struct Selectors {
+  // The table of contents
+  pub sidebar: CssSelector,
+  // Each section's entry in the table of contents, under the sidebar
+  pub sections: CssSelector,
+  // The links to each endpoint, under a section's entry
+  pub endpoints: CssSelector,
+  // The URIs of an endpoint that can be called through several, under the endpoint's element
+  pub uri_variants: CssSelector,
+  // The URI of an endpoint otherwise, under the endpoint's element
+  pub uri: CssSelector,
+  // The rows of an endpoint's parameter table
+  pub parameters: CssSelector,
+  pub description: CssSelector,
+  pub scopes: CssSelector,
+}
+
+struct DefaultSelectors {
+  sidebar: &'static str,
+  sections: &'static str,
+  endpoints: &'static str,
+  uri_variants: &'static str,
+  uri: &'static str,
+  parameters: &'static str,
+  description: &'static str,
+  scopes: &'static str,
+}
+
+impl Selectors {
+  fn new(defaults: &DefaultSelectors, replacements: &config::Selectors) -> Result<Selectors, ScrapeError> {
+    let selector = |default: &str, replacement: &Option<String>| CssSelector::parse(replacement.as_deref().unwrap_or(default));
+
+    Ok(Selectors {
+      sidebar: selector(defaults.sidebar, &replacements.sidebar)?,
+      sections: selector(defaults.sections, &replacements.sections)?,
+      endpoints: selector(defaults.endpoints, &replacements.endpoints)?,
+      uri_variants: selector(defaults.uri_variants, &replacements.uri_variants)?,
+      uri: selector(defaults.uri, &replacements.uri)?,
+      parameters: selector(defaults.parameters, &replacements.parameters)?,
+      description: selector(defaults.description, &replacements.description)?,
+      scopes: selector(defaults.scopes, &replacements.scopes)?,
+    })
+  }
+}
+
+// Most recent first, as the live page is what's scraped most often
+pub const LAYOUT_NAMES: [&str; 2] = ["current", "legacy"];
+
+// The layout with the name, with any of its selectors the config replaces
+pub fn by_name(name: &str, replacements: &config::LayoutSelectors) -> Result<Option<Box<dyn Layout>>, ScrapeError> {
+  Ok(match name {
+    "current" => Some(Box::new(Current::new(&replacements.current)?)),
+    "legacy" => Some(Box::new(Legacy::new(&replacements.legacy)?)),
+    _ => None,
+  })
+}
+
+pub fn detect(document: &Html, replacements: &config::LayoutSelectors) -> Result<Option<Box<dyn Layout>>, ScrapeError> {
+  for name in LAYOUT_NAMES {
+    if let Some(layout) = by_name(name, replacements)? {
+      if layout.detect(document) {
+        return Ok(Some(layout));
+      }
+    }
+  }
+
+  Ok(None)
+}
+
+/*
+ * Checks that the landmarks scraping relies on are all there before scraping anything, so that when the docs are
+ * restyled the run fails naming what's missing, instead of quietly generating next to nothing.
+ */
+pub fn preflight(layout: &dyn Layout, index: &DocumentIndex, min_endpoints: usize) -> Result<(), ScrapeError> {
+  let selectors = layout.selectors();
+  let missing = |landmark: &str, selector: &CssSelector| ScrapeError::MissingLandmark {
+    layout: layout.name().to_string(),
+    landmark: landmark.to_string(),
+    selector: selector.text.clone(),
+  };
+
+  let sidebar = index
+    .document()
+    .select(&selectors.sidebar.selector)
+    .next()
+    .ok_or_else(|| missing("the sidebar", &selectors.sidebar))?;
+  if sidebar.select(&selectors.sections.selector).next().is_none() {
+    return Err(missing("sections in the table of contents", &selectors.sections));
+  }
+  if sidebar.select(&selectors.endpoints.selector).next().is_none() {
+    return Err(missing("endpoints in the table of contents", &selectors.endpoints));
+  }
+
+  let documented = sidebar
+    .select(&selectors.endpoints.selector)
+    .filter_map(|link| link.value().attr("href"))
+    .filter_map(|href| index.by_anchor(href))
+    .filter(|details| {
+      details.select(&selectors.uri_variants.selector).next().is_some()
+        || details.select(&selectors.uri.selector).next().is_some()
+    })
+    .count();
+  if documented < min_endpoints {
+    return Err(ScrapeError::TooFewEndpoints {
+      layout: layout.name().to_string(),
+      selector: selectors.uri_variants.text.clone() + "' or '" + &selectors.uri.text,
+      expected: min_endpoints,
+      found: documented,
+    });
+  }
+
+  Ok(())
+}
+
+// The live page: a sidebar table of contents nested under an overall "reddit api documentation" entry
+pub struct Current {
+  selectors: Selectors,
+}
+
+const CURRENT_SELECTORS: DefaultSelectors = DefaultSelectors {
+  sidebar: "div.content div.sidebar",
+  sections: "div.toc > ul > li > ul > li",
+  endpoints: "div.toc > ul > li > ul > li > ul > li > a",
+  uri_variants: ".uri-variants li",
+  uri: "h3",
+  parameters: "table.parameters > tbody > tr",
+  description: ".info > .md",
+  scopes: "h3 .oauth-scope",
+};
+
+impl Current {
+  pub fn new(replacements: &config::Selectors) -> Result<Current, ScrapeError> {
+    Ok(Current {
+      selectors: Selectors::new(&CURRENT_SELECTORS, replacements)?,
+    })
+  }
+}
+
+impl Layout for Current {
+  fn name(&self) -> &'static str {
+    "current"
+  }
+
+  fn selectors(&self) -> &Selectors {
+    &self.selectors
+  }
+
+  fn detect(&self, document: &Html) -> bool {
+    has_endpoint_links(document, &self.selectors)
+  }
+
+  fn sections<'a>(&self, index: &DocumentIndex<'a>) -> Vec<Result<SidebarSection<'a>, ScrapeError>> {
+    sections_from_toc(index, &self.selectors)
+  }
+
+  fn uri_prototypes(&self, details: ElementRef) -> Vec<String> {
+    let variants: Vec<String> = details
+      .select(&self.selectors.uri_variants.selector)
+      .filter_map(collect_children_as_string)
+      .map(|variant| variant.trim_start_matches('→').trim().to_string())
+      .collect();
+
+    if !variants.is_empty() {
+      return variants;
+    }
+
+    get_api_from_api_details(details, &self.selectors).into_iter().collect()
+  }
+
+  fn parameters(&self, details: ElementRef, failures: &mut Vec<ScrapeError>) -> IndexMap<String, String> {
+    get_request_body_from_api_details(details, &self.selectors, failures)
+  }
+
+  fn description(&self, details: ElementRef) -> String {
+    markdown_of_first(details, &self.selectors.description)
+  }
+
+  fn scopes(&self, details: ElementRef) -> Vec<String> {
+    texts_of(details, &self.selectors.scopes)
+  }
+
+  fn is_listing(&self, details: ElementRef) -> bool {
+    says_it_is_a_listing(details, &self.selectors.description)
+  }
+
+  fn overview(&self, index: &DocumentIndex) -> Overview {
+    overview_from_headings(index)
+  }
+
+  fn section_description(&self, section: ElementRef) -> String {
+    markdown_of_child(section, "md")
+  }
+}
+
+/*
+ * Older copies of the page: no div.content wrapper, sections at the top level of the table of contents, no URI
+ * variants, and parameter descriptions directly in the table cell rather than in paragraphs. Links in Wayback Machine
+ * copies are often rewritten to absolute archive URLs, which is handled for every layout by only looking at the
+ * fragment.
+ */
+pub struct Legacy {
+  selectors: Selectors,
+}
+
+const LEGACY_SELECTORS: DefaultSelectors = DefaultSelectors {
+  sidebar: "div.sidebar",
+  sections: "div.toc > ul > li",
+  endpoints: "div.toc > ul > li > ul > li > a",
+  // Never there, but checked for along with the URI when checking the landmarks
+  uri_variants: ".uri-variants li",
+  uri: "h3",
+  parameters: "table.parameters > tbody > tr",
+  description: "div.endpoint > .md",
+  scopes: "h3 .oauth-scope",
+};
+
+impl Legacy {
+  pub fn new(replacements: &config::Selectors) -> Result<Legacy, ScrapeError> {
+    Ok(Legacy {
+      selectors: Selectors::new(&LEGACY_SELECTORS, replacements)?,
+    })
+  }
+}
+
+impl Layout for Legacy {
+  fn name(&self) -> &'static str {
+    "legacy"
+  }
+
+  fn selectors(&self) -> &Selectors {
+    &self.selectors
+  }
+
+  fn detect(&self, document: &Html) -> bool {
+    has_endpoint_links(document, &self.selectors)
+  }
+
+  fn sections<'a>(&self, index: &DocumentIndex<'a>) -> Vec<Result<SidebarSection<'a>, ScrapeError>> {
+    sections_from_toc(index, &self.selectors)
+  }
+
+  fn uri_prototypes(&self, details: ElementRef) -> Vec<String> {
+    get_api_from_api_details(details, &self.selectors).into_iter().collect()
+  }
+
+  fn parameters(&self, details: ElementRef, failures: &mut Vec<ScrapeError>) -> IndexMap<String, String> {
+    get_request_body_from_api_details(details, &self.selectors, failures)
+  }
+
+  fn description(&self, details: ElementRef) -> String {
+    markdown_of_first(details, &self.selectors.description)
+  }
+
+  // Copies from before the OAuth badges were added simply have none
+  fn scopes(&self, details: ElementRef) -> Vec<String> {
+    texts_of(details, &self.selectors.scopes)
+  }
+
+  fn is_listing(&self, details: ElementRef) -> bool {
+    says_it_is_a_listing(details, &self.selectors.description)
+  }
+
+  fn overview(&self, index: &DocumentIndex) -> Overview {
+    overview_from_headings(index)
+  }
+
+  fn section_description(&self, section: ElementRef) -> String {
+    markdown_of_child(section, "md")
+  }
+}
+
+// Selectors every layout uses the same way, parsed the first time they're needed
+struct FixedSelectors {
+  link: Selector,
+  table_rows: Selector,
+  header_cells: Selector,
+  cells: Selector,
+  list_items: Selector,
+}
+
+fn fixed_selectors() -> &'static FixedSelectors {
+  static FIXED_SELECTORS: OnceLock<FixedSelectors> = OnceLock::new();
+  FIXED_SELECTORS.get_or_init(|| FixedSelectors {
+    link: Selector::parse("a").unwrap(),
+    table_rows: Selector::parse("tbody > tr").unwrap(),
+    header_cells: Selector::parse("th").unwrap(),
+    cells: Selector::parse("td").unwrap(),
+    list_items: Selector::parse("li").unwrap(),
+  })
+}
+
+fn has_endpoint_links(document: &Html, selectors: &Selectors) -> bool {
+  document
+    .select(&selectors.sidebar.selector)
+    .any(|sidebar| sidebar.select(&selectors.endpoints.selector).next().is_some())
+}
+
+fn sections_from_toc<'a>(index: &DocumentIndex<'a>, selectors: &Selectors) -> Vec<Result<SidebarSection<'a>, ScrapeError>> {
+  let div_sidebar = match index.document().select(&selectors.sidebar.selector).next() {
+    Some(div_sidebar) => div_sidebar,
+    None => return Vec::new(),
+  };
+
+  let mut sections = Vec::new();
+  for element in div_sidebar.select(&selectors.sections.selector) {
+    let api_section_link = match element.select(&fixed_selectors().link).next() {
+      Some(api_section_link) => api_section_link,
+      None => {
+        sections.push(Err(ScrapeError::MissingElement {
+          section: String::new(),
+          anchor: String::new(),
+          selector: selectors.sections.text.clone() + " a",
+        }));
+        continue;
+      }
+    };
+    let api_section_header = api_section_link.text().collect::<String>().trim().to_string();
+
+    let mut endpoints = Vec::new();
+    for api_section in element.select(&selectors.endpoints.selector) {
+      // E.g. #GET_wiki_{page}, or https://web.archive.org/web/.../dev/api#GET_wiki_{page}
+      let href_to_api = match api_section.value().attr("href") {
+        Some(href_to_api) => href_to_api,
+        None => {
+          endpoints.push(Err(ScrapeError::MissingAttribute {
+            section: api_section_header.clone(),
+            anchor: String::new(),
+            selector: selectors.endpoints.text.clone(),
+            attribute: "href".to_string(),
+          }));
+          continue;
+        }
+      };
+      let anchor = match href_to_api.rfind('#') {
+        Some(i) => &href_to_api[i + 1..],
+        None => href_to_api,
+      };
+
+      endpoints.push(Ok(SidebarEndpoint {
+        anchor: anchor.to_string(),
+        details: index.by_anchor(anchor),
+      }));
+    }
+
+    let details = api_section_link.value().attr("href").and_then(|href| index.by_anchor(href));
+    sections.push(Ok(SidebarSection {
+      name: api_section_header,
+      endpoints,
+      details,
+    }));
+  }
+
+  sections
+}
+
+// The first direct child with the class, as Markdown, e.g. a section's description but not its endpoints'
+fn markdown_of_child(parent: ElementRef, class: &str) -> String {
+  let child = parent
+    .children()
+    .filter_map(ElementRef::wrap)
+    .find(|child| child.value().classes().any(|child_class| child_class == class));
+
+  match child {
+    Some(child) => markdown::from_html(child),
+    None => String::new(),
+  }
+}
+
+/*
+ * The overview is written as a few headings (fullnames, listings, modhashes) that the endpoints link to by id, each
+ * followed by paragraphs, plus a table of the fullname prefixes and a list of the parameters listings take. Anything
+ * missing (older copies don't explain everything) is left empty.
+ */
+fn overview_from_headings(index: &DocumentIndex) -> Overview {
+  let fullnames = elements_under_heading(index, "fullnames");
+  let listings = elements_under_heading(index, "listings");
+
+  Overview {
+    fullnames: markdown::from_elements(&without(&fullnames, "table")),
+    thing_kinds: thing_kinds(&fullnames),
+    listings: markdown::from_elements(&without(&listings, "ul")),
+    listing_parameters: listing_parameters(&listings),
+    modhashes: markdown::from_elements(&elements_under_heading(index, "modhashes")),
+  }
+}
+
+// Everything after the element with the id up to the next heading
+fn elements_under_heading<'a>(index: &DocumentIndex<'a>, id: &str) -> Vec<ElementRef<'a>> {
+  let heading = match index.by_id(id) {
+    Some(heading) => heading,
+    None => return Vec::new(),
+  };
+
+  heading
+    .next_siblings()
+    .filter_map(ElementRef::wrap)
+    .take_while(|element| !matches!(element.value().name(), "h1" | "h2" | "h3" | "h4" | "h5" | "h6"))
+    .collect()
+}
+
+fn without<'a>(elements: &[ElementRef<'a>], name: &str) -> Vec<ElementRef<'a>> {
+  elements
+    .iter()
+    .copied()
+    .filter(|element| element.value().name() != name)
+    .collect()
+}
+
+// From the table of type prefixes, e.g. t1_ | Comment
+fn thing_kinds(fullnames: &[ElementRef]) -> Vec<ThingKind> {
+  let table = match fullnames.iter().find(|element| element.value().name() == "table") {
+    Some(table) => table,
+    None => return Vec::new(),
+  };
+
+  table
+    .select(&fixed_selectors().table_rows)
+    .filter_map(|row| {
+      let cells: Vec<String> = row
+        .select(&fixed_selectors().cells)
+        .map(|cell| cell.text().collect::<String>().trim().to_string())
+        .collect();
+      match cells.as_slice() {
+        [prefix, name, ..] if !prefix.is_empty() && !name.is_empty() => Some(ThingKind {
+          prefix: prefix.to_string(),
+          name: name.to_string(),
+        }),
+        _ => None,
+      }
+    })
+    .collect()
+}
+
+// From the list of parameters, e.g. "`after` / `before` - only one should be specified", one per name
+fn listing_parameters(listings: &[ElementRef]) -> Vec<ListingParameter> {
+  let list = match listings.iter().find(|element| element.value().name() == "ul") {
+    Some(list) => list,
+    None => return Vec::new(),
+  };
+
+  let mut parameters = Vec::new();
+  for item in list.select(&fixed_selectors().list_items) {
+    let item = markdown::from_html(item);
+    let (names, description) = match item.find(" - ") {
+      Some(i) => (&item[..i], item[i + 3..].trim()),
+      None => continue,
+    };
+
+    for name in names.split('/') {
+      let name = name.trim().trim_matches('`');
+      if !name.is_empty() {
+        parameters.push(ListingParameter {
+          name: name.to_string(),
+          description: description.to_string(),
+        });
+      }
+    }
+  }
+
+  parameters
+}
+
+/*
+ * Parameter names are the text of their header cell, so entities are decoded and any markup (links, code) is dropped.
+ * Descriptions are the whole of their cell as Markdown: every paragraph and list, with inline code kept as `code`.
+ */
+fn get_request_body_from_api_details(
+  api_detail: ElementRef,
+  selectors: &Selectors,
+  failures: &mut Vec<ScrapeError>,
+) -> IndexMap<String, String> {
+  let parameter_row_selection = api_detail.select(&selectors.parameters.selector);
+
+  let mut request_fields = IndexMap::new();
+  for selection in parameter_row_selection {
+    let parameter_name = match selection.select(&fixed_selectors().header_cells).next() {
+      Some(parameter_name) => parameter_name,
+      None => {
+        failures.push(ScrapeError::MissingElement {
+          section: String::new(),
+          anchor: String::new(),
+          selector: selectors.parameters.text.clone() + " > th",
+        });
+        continue;
+      }
+    };
+    let parameter_name = parameter_name.text().collect::<String>();
+    let parameter_description = selection.select(&fixed_selectors().cells).next();
+    request_fields.insert(
+      parameter_name.split_whitespace().collect::<Vec<_>>().join(" "),
+      match parameter_description {
+        Some(description) => markdown::from_html(description),
+        None => "".to_string(),
+      },
+    );
+  }
+
+  request_fields
+}
+
+fn get_api_from_api_details(api_detail: ElementRef, selectors: &Selectors) -> Option<String> {
+  // Assuming there's only one...
+  api_detail
+    .select(&selectors.uri.selector)
+    .next()
+    .and_then(collect_children_as_string)
+    .map(|api| api.trim().to_string())
+}
+
+fn markdown_of_first(parent: ElementRef, selector: &CssSelector) -> String {
+  match parent.select(&selector.selector).next() {
+    Some(element) => markdown::from_html(element),
+    None => String::new(),
+  }
+}
+
+/*
+ * Listings are marked with a "This endpoint is a listing." note in their description, the words "a listing" linking to
+ * the overview of listings in more recent copies of the docs.
+ */
+fn says_it_is_a_listing(parent: ElementRef, description_selector: &CssSelector) -> bool {
+  parent.select(&description_selector.selector).any(|description| {
+    let text = description.text().collect::<String>();
+    text.split_whitespace().collect::<Vec<_>>().join(" ").contains("This endpoint is a listing")
+  })
+}
+
+fn texts_of(parent: ElementRef, selector: &CssSelector) -> Vec<String> {
+  parent
+    .select(&selector.selector)
+    .map(|element| element.text().collect::<String>().trim().to_string())
+    .filter(|text| !text.is_empty())
+    .collect()
+}
+
+fn collect_children_as_string(parent: ElementRef) -> Option<String> {
+  let mut uri_parts: Vec<String> = Vec::new();
+  for child in parent.children() {
+    match (*child.value()).as_element() {
+      Some(element) => {
+        if element.name() == "span" || element.name() == "a" {
+          continue;
+        }
+
+        let element_ref = ElementRef::wrap(child).unwrap();
+        if element.name() == "em" {
+          uri_parts.push("{{".to_string() + &element_ref.inner_html().to_string() + "}}");
+        } else {
+          uri_parts.push(element_ref.inner_html());
+        }
+      }
+      // Comments and the like aren't part of the URI
+      _ => {
+        if let Some(text) = child.value().as_text() {
+          uri_parts.push(text.to_string());
+        }
+      }
+    }
+  }
+
+  let mut uri_as_string = String::new();
+  for uri_part in uri_parts {
+    uri_as_string.push_str(&uri_part);
+  }
+
+  if uri_as_string.is_empty() {
+    None
+  } else {
+    Some(uri_as_string)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn replaced_selectors_only_apply_to_their_own_layout() {
+    let scraping: config::Scraping = toml::from_str("[selectors.current]\nsidebar = \"nav.sidebar\"\n").unwrap();
+
+    let current = by_name("current", &scraping.selectors).unwrap().unwrap();
+    assert_eq!(current.selectors().sidebar.text, "nav.sidebar");
+    assert_eq!(current.selectors().sections.text, CURRENT_SELECTORS.sections);
+
+    let legacy = by_name("legacy", &scraping.selectors).unwrap().unwrap();
+    assert_eq!(legacy.selectors().sidebar.text, LEGACY_SELECTORS.sidebar);
+  }
+
+  const SECTIONS: &str = r##"<ul><li><a href="#api_overview">reddit api documentation</a><ul>
+      <li><a href="#section_account">account</a><ul>
+        <li><a href="#GET_api_v1_me">/api/v1/me</a></li>
+        <li><a href="#GET_api_v1_me_karma">/api/v1/me/karma</a></li>
+      </ul></li>
+    </ul></li></ul>"##;
+
+  const DETAILS: &str = r##"<div class="endpoint" id="GET_api_v1_me"><h3>/api/v1/me</h3></div>
+    <div class="endpoint" id="GET_api_v1_me_karma"><div class="md"><p>No URI</p></div></div>"##;
+
+  fn preflight_current(html: &str, min_endpoints: usize) -> Result<(), ScrapeError> {
+    let document = Html::parse_document(html);
+    let index = DocumentIndex::new(&document);
+    preflight(&Current::new(&config::Selectors::default()).unwrap(), &index, min_endpoints)
+  }
+
+  fn page(toc: &str, details: &str) -> String {
+    format!(
+      r#"<div class="content"><div class="sidebar"><div class="toc">{}</div></div>{}</div>"#,
+      toc, details
+    )
+  }
+
+  fn missing(landmark: &str, selector: &str) -> ScrapeError {
+    ScrapeError::MissingLandmark {
+      layout: "current".to_string(),
+      landmark: landmark.to_string(),
+      selector: selector.to_string(),
+    }
+  }
+
+  #[test]
+  fn a_page_with_every_landmark_passes() {
+    assert_eq!(preflight_current(&page(SECTIONS, DETAILS), 1), Ok(()));
+  }
+
+  #[test]
+  fn a_missing_sidebar_is_named() {
+    assert_eq!(
+      preflight_current(&format!(r#"<div class="content"><nav class="toc">{}</nav>{}</div>"#, SECTIONS, DETAILS), 1),
+      Err(missing("the sidebar", CURRENT_SELECTORS.sidebar))
+    );
+  }
+
+  #[test]
+  fn missing_sections_are_named() {
+    assert_eq!(
+      preflight_current(&page(r##"<ul><li><a href="#api_overview">docs</a></li></ul>"##, DETAILS), 1),
+      Err(missing("sections in the table of contents", CURRENT_SELECTORS.sections))
+    );
+  }
+
+  #[test]
+  fn missing_endpoints_are_named() {
+    let toc = r##"<ul><li><a href="#api_overview">docs</a><ul>
+      <li><a href="#section_account">account</a></li>
+    </ul></li></ul>"##;
+    assert_eq!(
+      preflight_current(&page(toc, DETAILS), 1),
+      Err(missing("endpoints in the table of contents", CURRENT_SELECTORS.endpoints))
+    );
+  }
+
+  #[test]
+  fn too_few_endpoints_with_a_uri_are_counted() {
+    // Only one of the two endpoints has a URI where the layout expects one
+    assert_eq!(
+      preflight_current(&page(SECTIONS, DETAILS), 2),
+      Err(ScrapeError::TooFewEndpoints {
+        layout: "current".to_string(),
+        selector: format!("{}' or '{}", CURRENT_SELECTORS.uri_variants, CURRENT_SELECTORS.uri),
+        expected: 2,
+        found: 1,
+      })
+    );
+    assert!(matches!(
+      preflight_current(&page(SECTIONS, ""), 1),
+      Err(ScrapeError::TooFewEndpoints { found: 0, .. })
+    ));
+  }
+
+  #[test]
+  fn parameter_names_are_their_header_text_and_descriptions_are_markdown() {
+    let html = r##"<div class="endpoint" id="POST_api_comment"><table class="parameters"><tbody>
+      <tr><th scope="row">
+        <a href="#modhashes">uh</a> /
+        <code>X-Modhash</code>   header
+      </th><td><p>a <a href="#modhashes">modhash</a></p><p>sent as &lt;header&gt;</p></td></tr>
+      <tr><th scope="row">api_type</th></tr>
+    </tbody></table></div>"##;
+    let document = Html::parse_document(html);
+    let details = document.select(&Selector::parse("div.endpoint").unwrap()).next().unwrap();
+
+    let mut failures = Vec::new();
+    let parameters = Current::new(&config::Selectors::default())
+      .unwrap()
+      .parameters(details, &mut failures);
+    assert_eq!(
+      parameters.into_iter().collect::<Vec<_>>(),
+      [
+        (
+          "uh / X-Modhash header".to_string(),
+          "a [modhash](https://www.reddit.com/dev/api#modhashes)\n\nsent as <header>".to_string()
+        ),
+        ("api_type".to_string(), String::new()),
+      ]
+    );
+    assert_eq!(failures, []);
+  }
+
+  #[test]
+  fn each_fixture_is_detected_as_its_layout() {
+    for (html, name) in [
+      (include_str!("../fixtures/dev_api.html"), "current"),
+      (include_str!("../fixtures/dev_api_legacy.html"), "legacy"),
+    ] {
+      let document = Html::parse_document(html);
+      let layout = detect(&document, &config::LayoutSelectors::default()).unwrap().unwrap();
+      assert_eq!(layout.name(), name);
+    }
+  }
+}
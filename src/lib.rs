@@ -0,0 +1,42 @@
+/*
+ * Scrapes the reddit API documentation (https://www.reddit.com/dev/api) into an ApiCatalog and generates Rust bindings
+ * from it. The reddit_api_scraper binary is a command-line interface over this; other tools can embed it directly:
+ *
+ *   let catalog = reddit_api_scraper::scrape_document(&html)?;
+ *   let files = reddit_api_scraper::generate(&catalog, &reddit_api_scraper::Options::default())?;
+ *   files.write(Path::new("src/api"))?;
+ *
+ * or from a build script, see build_script::generate.
+ */
+#[macro_use]
+pub mod verbosity;
+
+pub mod api_scraper;
+pub mod build_script;
+pub mod catalog;
+pub mod config;
+pub mod diff;
+pub mod document_index;
+pub mod generator;
+pub mod history;
+pub mod http_verb;
+pub mod layout;
+pub mod markdown;
+pub mod scopes;
+pub mod scrape_error;
+pub mod snapshot;
+pub mod source;
+pub mod template_uri;
+pub mod validation;
+
+pub use catalog::ApiCatalog;
+pub use generator::{generate, GeneratedFiles, Options};
+pub use scrape_error::ScrapeError;
+
+/*
+ * Scrapes a copy of the docs, whichever layout it uses. Entries that can't be scraped are left out and listed in the
+ * catalog's failures rather than failing the whole document.
+ */
+pub fn scrape_document(html: &str) -> Result<ApiCatalog, ScrapeError> {
+  api_scraper::scrape_catalog(html, None, &config::Scraping::default())
+}
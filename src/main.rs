@@ -1,13 +1,86 @@
-mod api_scraper;
-mod generator;
-mod http_verb;
-mod template_uri;
+// Like the library's, printing progress to stderr at the level set from the arguments
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if reddit_api_scraper::verbosity::at_least(reddit_api_scraper::verbosity::NORMAL) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if reddit_api_scraper::verbosity::at_least(reddit_api_scraper::verbosity::VERBOSE) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+mod cli;
+mod commands;
+mod watch;
+
+use clap::Parser;
+use cli::{Cli, Command};
+use commands::Outcome;
+use reddit_api_scraper::{config, verbosity};
+use std::process::ExitCode;
+
+/*
+ * Exit codes: 0 when everything went fine, 1 when `diff` or `check` found differences and 2 when something went
+ * wrong (matching clap's exit code for bad arguments).
+ */
+const EXIT_DIFFERENCES: u8 = 1;
+const EXIT_ERROR: u8 = 2;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let html = reqwest::get("https://www.reddit.com/dev/api").await?.text().await?;
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    verbosity::set(match (cli.quiet, cli.verbose) {
+        (true, _) => verbosity::QUIET,
+        (false, 0) => verbosity::NORMAL,
+        (false, _) => verbosity::VERBOSE,
+    });
+
+    let config = match &cli.config {
+        Some(path) => config::Config::load(path),
+        None => config::Config::discover(),
+    };
+    let config = match config {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return ExitCode::from(EXIT_ERROR);
+        }
+    };
 
-    api_scraper::scrape(&html).await?;
+    let result = match &cli.command {
+        Command::Fetch { url, output } => commands::fetch(url, output.as_deref(), &config).await,
+        Command::Scrape { input, catalog } => commands::scrape(input, catalog.as_deref(), &config).await,
+        Command::Generate { input, from, output } => commands::generate(input, from.as_deref(), output, &config).await,
+        Command::Diff { old, new, format } => commands::diff(old, new, *format, &config).await,
+        Command::History { directory, format } => commands::history(directory, *format, &config).await,
+        Command::Watch {
+            input,
+            output,
+            interval,
+            webhook,
+        } => commands::watch(input, output, *interval, webhook.as_deref(), &config).await,
+        Command::Check { input, output } => commands::check(input, output, &config).await,
+        Command::Explain {
+            anchor,
+            input,
+            layout,
+            targets,
+        } => commands::explain(anchor, input, layout.as_deref(), targets, &config).await,
+    };
 
-    Ok(())
+    match result {
+        Ok(Outcome::Success) => ExitCode::SUCCESS,
+        Ok(Outcome::Differences) => ExitCode::from(EXIT_DIFFERENCES),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
 }
@@ -0,0 +1,213 @@
+use crate::source::DEFAULT_URL;
+
+use ego_tree::NodeRef;
+use scraper::element_ref::ElementRef;
+use scraper::Node;
+
+/*
+ * The docs are written in Markdown and rendered to HTML, so turning the HTML back into Markdown gets close to what was
+ * originally written. Only the handful of elements the docs actually use are handled; anything else is reduced to its
+ * text. Relative links are made absolute so they still work from the generated code's documentation.
+ */
+pub fn from_html(element: ElementRef) -> String {
+  let mut blocks = Vec::new();
+  let mut inline = String::new();
+  collect_blocks(element.children(), &mut blocks, &mut inline);
+  end_block(&mut blocks, &mut inline);
+
+  blocks.join("\n\n")
+}
+
+// The same for a run of elements rather than the children of one, e.g. everything between two headings
+pub fn from_elements(elements: &[ElementRef]) -> String {
+  let mut blocks = Vec::new();
+  let mut inline = String::new();
+  collect_blocks(elements.iter().map(|element| **element), &mut blocks, &mut inline);
+  end_block(&mut blocks, &mut inline);
+
+  blocks.join("\n\n")
+}
+
+fn collect_blocks<'a>(nodes: impl Iterator<Item = NodeRef<'a, Node>>, blocks: &mut Vec<String>, inline: &mut String) {
+  for child in nodes {
+    let element = match ElementRef::wrap(child) {
+      Some(element) => element,
+      None => {
+        if let Some(text) = child.value().as_text() {
+          inline.push_str(text);
+        }
+        continue;
+      }
+    };
+
+    match element.value().name() {
+      "p" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+        end_block(blocks, inline);
+        inline.push_str(&inline_markdown(element));
+        end_block(blocks, inline);
+      }
+      "div" | "blockquote" => {
+        end_block(blocks, inline);
+        collect_blocks(element.children(), blocks, inline);
+        end_block(blocks, inline);
+      }
+      "ul" | "ol" => {
+        end_block(blocks, inline);
+        let ordered = element.value().name() == "ol";
+        let items: Vec<String> = element
+          .children()
+          .filter_map(ElementRef::wrap)
+          .filter(|item| item.value().name() == "li")
+          .enumerate()
+          .map(|(i, item)| {
+            let marker = if ordered { (i + 1).to_string() + "." } else { "-".to_string() };
+            marker + " " + &normalize_whitespace(&inline_markdown(item))
+          })
+          .collect();
+        if !items.is_empty() {
+          blocks.push(items.join("\n"));
+        }
+      }
+      "pre" => {
+        end_block(blocks, inline);
+        let code = element.text().collect::<String>();
+        blocks.push("```text\n".to_string() + code.trim_end() + "\n```");
+      }
+      _ => inline.push_str(&inline_markdown_of_element(element)),
+    }
+  }
+}
+
+fn end_block(blocks: &mut Vec<String>, inline: &mut String) {
+  let block = normalize_whitespace(inline);
+  if !block.is_empty() {
+    blocks.push(block);
+  }
+  inline.clear();
+}
+
+fn inline_markdown(parent: ElementRef) -> String {
+  let mut markdown = String::new();
+  for child in parent.children() {
+    match ElementRef::wrap(child) {
+      Some(element) => markdown.push_str(&inline_markdown_of_element(element)),
+      None => {
+        if let Some(text) = child.value().as_text() {
+          markdown.push_str(text);
+        }
+      }
+    }
+  }
+
+  markdown
+}
+
+fn inline_markdown_of_element(element: ElementRef) -> String {
+  match element.value().name() {
+    "code" => "`".to_string() + element.text().collect::<String>().trim() + "`",
+    "em" | "i" => wrap_inline("*", &inline_markdown(element)),
+    "strong" | "b" => wrap_inline("**", &inline_markdown(element)),
+    "br" => " ".to_string(),
+    "a" => {
+      let text = inline_markdown(element);
+      match element.value().attr("href") {
+        Some(href) => "[".to_string() + text.trim() + "](" + &absolute_link(href) + ")",
+        None => text,
+      }
+    }
+    _ => inline_markdown(element),
+  }
+}
+
+// Emphasis markers have to hug the text they apply to, so surrounding whitespace goes outside them
+fn wrap_inline(marker: &str, text: &str) -> String {
+  if text.trim().is_empty() {
+    return text.to_string();
+  }
+
+  let leading = if text.starts_with(char::is_whitespace) { " " } else { "" };
+  let trailing = if text.ends_with(char::is_whitespace) { " " } else { "" };
+  leading.to_string() + marker + text.trim() + marker + trailing
+}
+
+// E.g. #fullnames -> https://www.reddit.com/dev/api#fullnames, /wiki/search -> https://www.reddit.com/wiki/search
+fn absolute_link(href: &str) -> String {
+  if href.starts_with('#') {
+    DEFAULT_URL.to_string() + href
+  } else if href.starts_with('/') && !href.starts_with("//") {
+    let origin_end = DEFAULT_URL.find("://").map_or(0, |i| i + 3);
+    let origin = match DEFAULT_URL[origin_end..].find('/') {
+      Some(i) => &DEFAULT_URL[..origin_end + i],
+      None => DEFAULT_URL,
+    };
+    origin.to_string() + href
+  } else {
+    href.to_string()
+  }
+}
+
+fn normalize_whitespace(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use scraper::{Html, Selector};
+
+  fn markdown(html: &str) -> String {
+    let fragment = Html::parse_fragment(&format!("<div class=\"md\">{}</div>", html));
+    let md = fragment.select(&Selector::parse("div.md").unwrap()).next().unwrap();
+    from_html(md)
+  }
+
+  #[test]
+  fn entities_are_decoded() {
+    assert_eq!(
+      markdown("<p>links &amp; comments, where &lt;id&gt; is a &quot;fullname&quot;</p>"),
+      "links & comments, where <id> is a \"fullname\""
+    );
+  }
+
+  #[test]
+  fn code_stays_code() {
+    assert_eq!(
+      markdown("<p>one of (<code>link</code>, <code> self </code>)</p>"),
+      "one of (`link`, `self`)"
+    );
+  }
+
+  #[test]
+  fn markup_is_stripped_down_to_its_text() {
+    assert_eq!(
+      markdown("<p><span class=\"note\">an <em>optional</em> <strong>integer</strong></span><br>at most 100</p>"),
+      "an *optional* **integer** at most 100"
+    );
+  }
+
+  #[test]
+  fn relative_links_are_made_absolute() {
+    assert_eq!(
+      markdown(
+        "<p>a <a href=\"#fullnames\">fullname</a>, see <a href=\"/wiki/search\">search</a> or \
+         <a href=\"https://github.com/reddit\">the source</a></p>"
+      ),
+      "a [fullname](https://www.reddit.com/dev/api#fullnames), see [search](https://www.reddit.com/wiki/search) or \
+       [the source](https://github.com/reddit)"
+    );
+  }
+
+  #[test]
+  fn paragraphs_and_lists_are_separate_blocks() {
+    assert_eq!(
+      markdown(
+        "<p>Get the
+           identity of the user.</p>
+         <p>Either:</p>
+         <ul><li>the <code>id</code></li><li>the <em>name</em></li></ul>
+         <pre>{ \"id\": 1 }\n</pre>"
+      ),
+      "Get the identity of the user.\n\nEither:\n\n- the `id`\n- the *name*\n\n```text\n{ \"id\": 1 }\n```"
+    );
+  }
+}
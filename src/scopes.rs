@@ -0,0 +1,213 @@
+use crate::catalog::{ApiCatalog, Scope};
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+// Scopes documented on more than one page are combined, keeping the order they were first seen in
+pub fn merge(pages: Vec<Vec<Scope>>) -> Vec<Scope> {
+  let mut merged: Vec<Scope> = Vec::new();
+  for scope in pages.into_iter().flatten() {
+    match merged.iter_mut().find(|existing| existing.name == scope.name) {
+      Some(existing) => {
+        if existing.description.is_empty() {
+          existing.description = scope.description;
+        }
+        for endpoint in scope.endpoints {
+          if !existing.endpoints.contains(&endpoint) {
+            existing.endpoints.push(endpoint);
+          }
+        }
+      }
+      None => merged.push(scope),
+    }
+  }
+
+  merged
+}
+
+// Places where the scope pages and the scope badges on the endpoints disagree
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScopeMismatch {
+  // The endpoint's badge names the scope, but the scope's page doesn't list the endpoint
+  NotListed { anchor: String, scope: String },
+  // The scope's page lists the endpoint, but the endpoint has no badge for the scope
+  NotBadged { anchor: String, scope: String },
+  // The scope's page lists an endpoint that isn't in the docs
+  UnknownEndpoint { anchor: String, scope: String },
+}
+
+/*
+ * Only scopes that have been scraped from their pages can be checked, so badges naming other scopes are left alone:
+ * handing over the page of a single scope only checks that scope.
+ */
+pub fn cross_check(catalog: &ApiCatalog) -> Vec<ScopeMismatch> {
+  let badges: BTreeMap<&str, BTreeSet<&str>> = catalog
+    .endpoints()
+    .map(|(_, endpoint)| {
+      let scopes = endpoint.scopes.iter().map(String::as_str).collect();
+      (endpoint.anchor.as_str(), scopes)
+    })
+    .collect();
+
+  let mut mismatches = Vec::new();
+  for scope in &catalog.scopes {
+    for anchor in &scope.endpoints {
+      match badges.get(anchor.as_str()) {
+        None => mismatches.push(ScopeMismatch::UnknownEndpoint {
+          anchor: anchor.to_string(),
+          scope: scope.name.clone(),
+        }),
+        Some(scopes) if !scopes.contains(scope.name.as_str()) => mismatches.push(ScopeMismatch::NotBadged {
+          anchor: anchor.to_string(),
+          scope: scope.name.clone(),
+        }),
+        Some(_) => {}
+      }
+    }
+
+    for (anchor, scopes) in &badges {
+      if scopes.contains(scope.name.as_str()) && !scope.endpoints.iter().any(|endpoint| endpoint == anchor) {
+        mismatches.push(ScopeMismatch::NotListed {
+          anchor: anchor.to_string(),
+          scope: scope.name.clone(),
+        });
+      }
+    }
+  }
+
+  mismatches
+}
+
+impl fmt::Display for ScopeMismatch {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ScopeMismatch::NotListed { anchor, scope } => {
+        write!(f, "{} is badged with the {} scope, but isn't listed for it", anchor, scope)
+      }
+      ScopeMismatch::NotBadged { anchor, scope } => {
+        write!(f, "{} is listed for the {} scope, but isn't badged with it", anchor, scope)
+      }
+      ScopeMismatch::UnknownEndpoint { anchor, scope } => {
+        write!(f, "{} is listed for the {} scope, but isn't in the docs", anchor, scope)
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::catalog::{ApiSection, Endpoint};
+  use crate::http_verb::HttpVerb;
+
+  fn scope(name: &str, description: &str, endpoints: &[&str]) -> Scope {
+    Scope {
+      name: name.to_string(),
+      description: description.to_string(),
+      endpoints: endpoints.iter().map(|anchor| anchor.to_string()).collect(),
+    }
+  }
+
+  fn summary(scopes: &[Scope]) -> Vec<(&str, &str, Vec<&str>)> {
+    scopes
+      .iter()
+      .map(|scope| {
+        let endpoints = scope.endpoints.iter().map(String::as_str).collect();
+        (scope.name.as_str(), scope.description.as_str(), endpoints)
+      })
+      .collect()
+  }
+
+  #[test]
+  fn scopes_on_several_pages_are_merged() {
+    let merged = merge(vec![
+      vec![
+        scope("identity", "Access my reddit username.", &["GET_api_v1_me"]),
+        scope("read", "", &["GET_hot"]),
+      ],
+      vec![
+        scope("read", "Access posts and comments.", &["GET_hot", "GET_comments_{article}"]),
+        scope("identity", "Something else.", &["GET_api_v1_me", "GET_api_v1_me_prefs"]),
+        scope("wikiread", "Read wiki pages.", &["GET_wiki_{page}"]),
+      ],
+    ]);
+
+    assert_eq!(
+      summary(&merged),
+      [
+        (
+          "identity",
+          "Access my reddit username.",
+          vec!["GET_api_v1_me", "GET_api_v1_me_prefs"]
+        ),
+        ("read", "Access posts and comments.", vec!["GET_hot", "GET_comments_{article}"]),
+        ("wikiread", "Read wiki pages.", vec!["GET_wiki_{page}"]),
+      ]
+    );
+  }
+
+  fn badged(anchor: &str, scopes: &[&str]) -> Endpoint {
+    Endpoint {
+      anchor: anchor.to_string(),
+      verb: HttpVerb::GET,
+      uris: Vec::new(),
+      parameters: Vec::new(),
+      description: String::new(),
+      scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+      listing: false,
+    }
+  }
+
+  #[test]
+  fn mismatches_between_the_scope_pages_and_the_badges() {
+    let catalog = ApiCatalog {
+      sections: vec![ApiSection {
+        name: "account".to_string(),
+        endpoints: vec![
+          badged("GET_api_v1_me", &["identity", "read"]),
+          badged("GET_api_v1_me_karma", &["identity"]),
+          badged("GET_api_v1_me_prefs", &[]),
+        ],
+      }],
+      scopes: vec![scope(
+        "identity",
+        "Access my reddit username.",
+        &["GET_api_v1_me", "GET_api_v1_me_prefs", "GET_api_v1_me_trophies"],
+      )],
+      ..ApiCatalog::default()
+    };
+
+    // The read badge is left alone, as the read scope's page wasn't scraped
+    assert_eq!(
+      cross_check(&catalog),
+      [
+        ScopeMismatch::NotBadged {
+          anchor: "GET_api_v1_me_prefs".to_string(),
+          scope: "identity".to_string(),
+        },
+        ScopeMismatch::UnknownEndpoint {
+          anchor: "GET_api_v1_me_trophies".to_string(),
+          scope: "identity".to_string(),
+        },
+        ScopeMismatch::NotListed {
+          anchor: "GET_api_v1_me_karma".to_string(),
+          scope: "identity".to_string(),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn no_mismatches_without_scope_pages() {
+    let catalog = ApiCatalog {
+      sections: vec![ApiSection {
+        name: "account".to_string(),
+        endpoints: vec![badged("GET_api_v1_me", &["identity"])],
+      }],
+      ..ApiCatalog::default()
+    };
+    assert_eq!(cross_check(&catalog), []);
+  }
+}
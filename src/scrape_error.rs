@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/*
+ * Something in the docs that couldn't be scraped. Other than the document not looking like the docs at all, these are
+ * about one entry of the docs: the entry is left out and the failure is kept in the catalog, so the rest of the docs
+ * can still be used. The section and anchor are empty where they aren't known, e.g. for a section without a name.
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScrapeError {
+  UnknownLayout,
+  // Something the layout relies on isn't in the document at all, most likely because the docs have been restyled
+  MissingLandmark {
+    layout: String,
+    landmark: String,
+    selector: String,
+  },
+  TooFewEndpoints {
+    layout: String,
+    selector: String,
+    expected: usize,
+    found: usize,
+  },
+  MissingElement {
+    section: String,
+    anchor: String,
+    selector: String,
+  },
+  MissingAttribute {
+    section: String,
+    anchor: String,
+    selector: String,
+    attribute: String,
+  },
+  InvalidSelector {
+    section: String,
+    anchor: String,
+    selector: String,
+  },
+  UnknownVerb {
+    section: String,
+    anchor: String,
+    verb: String,
+  },
+  MalformedUri {
+    section: String,
+    anchor: String,
+    uri: String,
+    reason: String,
+  },
+}
+
+impl ScrapeError {
+  // Fills in where the failure happened, for failures found without knowing, e.g. in an endpoint's parameter table
+  pub fn at(mut self, in_section: &str, at_anchor: &str) -> ScrapeError {
+    match &mut self {
+      ScrapeError::UnknownLayout | ScrapeError::MissingLandmark { .. } | ScrapeError::TooFewEndpoints { .. } => {}
+      ScrapeError::MissingElement { section, anchor, .. }
+      | ScrapeError::MissingAttribute { section, anchor, .. }
+      | ScrapeError::InvalidSelector { section, anchor, .. }
+      | ScrapeError::UnknownVerb { section, anchor, .. }
+      | ScrapeError::MalformedUri { section, anchor, .. } => {
+        *section = in_section.to_string();
+        *anchor = at_anchor.to_string();
+      }
+    }
+
+    self
+  }
+
+  fn location(&self) -> String {
+    let (section, anchor) = match self {
+      ScrapeError::UnknownLayout | ScrapeError::MissingLandmark { .. } | ScrapeError::TooFewEndpoints { .. } => {
+        return String::new()
+      }
+      ScrapeError::MissingElement { section, anchor, .. }
+      | ScrapeError::MissingAttribute { section, anchor, .. }
+      | ScrapeError::InvalidSelector { section, anchor, .. }
+      | ScrapeError::UnknownVerb { section, anchor, .. }
+      | ScrapeError::MalformedUri { section, anchor, .. } => (section, anchor),
+    };
+
+    match (section.is_empty(), anchor.is_empty()) {
+      (true, true) => String::new(),
+      (false, true) => format!("section '{}': ", section),
+      (true, false) => format!("{}: ", anchor),
+      (false, false) => format!("section '{}', {}: ", section, anchor),
+    }
+  }
+}
+
+impl fmt::Display for ScrapeError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let location = self.location();
+    match self {
+      ScrapeError::UnknownLayout => write!(f, "The document doesn't look like any known layout of the API docs"),
+      ScrapeError::MissingLandmark {
+        layout,
+        landmark,
+        selector,
+      } => write!(
+        f,
+        "The docs don't have {} where the {} layout expects them ('{}'), the markup may have changed: see \
+         [scraping.selectors.{}] in the config",
+        landmark, layout, selector, layout
+      ),
+      ScrapeError::TooFewEndpoints {
+        layout,
+        selector,
+        expected,
+        found,
+      } => write!(
+        f,
+        "Only {} endpoint(s) are documented with a URI where the {} layout expects them ('{}'), rather than at least \
+         {}: the markup may have changed, see [scraping.selectors.{}] in the config",
+        found, layout, selector, expected, layout
+      ),
+      ScrapeError::MissingElement { selector, .. } => write!(f, "{}nothing matches '{}'", location, selector),
+      ScrapeError::MissingAttribute {
+        selector, attribute, ..
+      } => write!(f, "{}'{}' has no {} attribute", location, selector, attribute),
+      ScrapeError::InvalidSelector { selector, .. } => write!(f, "{}'{}' isn't a valid selector", location, selector),
+      ScrapeError::UnknownVerb { verb, .. } => write!(f, "{}'{}' isn't an HTTP verb", location, verb),
+      // The reason already quotes the URI
+      ScrapeError::MalformedUri { reason, .. } => write!(f, "{}{}", location, reason),
+    }
+  }
+}
+
+impl std::error::Error for ScrapeError {}
@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_SNAPSHOT_DIR: &str = "target/snapshots";
+
+const INDEX_FILE_NAME: &str = "index.json";
+
+// A fetched copy of the docs, stored as <sha256>.html with this alongside it as <sha256>.json
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+  pub url: String,
+  pub sha256: String,
+  // RFC 3339
+  pub fetched_at: String,
+  pub etag: Option<String>,
+  pub last_modified: Option<String>,
+}
+
+/*
+ * Keeps every distinct version of the docs that has been fetched, keyed by content hash, plus an index of the latest
+ * snapshot for each URL. The stored validators (ETag, Last-Modified) make re-fetching a conditional request, so an
+ * unchanged page isn't downloaded again.
+ */
+pub struct SnapshotStore {
+  directory: PathBuf,
+}
+
+impl SnapshotStore {
+  pub fn new(directory: &Path) -> SnapshotStore {
+    SnapshotStore {
+      directory: directory.to_path_buf(),
+    }
+  }
+
+  pub async fn fetch(&self, url: &str) -> Result<(Snapshot, String), Box<dyn std::error::Error>> {
+    let latest = self.latest(url)?;
+
+    let mut request = reqwest::Client::new().get(url);
+    if let Some(latest) = &latest {
+      if let Some(etag) = &latest.etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+      }
+      if let Some(last_modified) = &latest.last_modified {
+        request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+      }
+    }
+
+    let response = request.send().await?;
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+      if let Some(latest) = latest {
+        info!("{} not modified since {}, using snapshot {}", url, latest.fetched_at, latest.sha256);
+        let html = self.read(&latest)?;
+        return Ok((latest, html));
+      }
+    }
+
+    let response = response.error_for_status()?;
+    let etag = header(&response, reqwest::header::ETAG);
+    let last_modified = header(&response, reqwest::header::LAST_MODIFIED);
+    let html = response.text().await?;
+
+    let snapshot = self.save(url, &html, etag, last_modified)?;
+    Ok((snapshot, html))
+  }
+
+  pub fn save(
+    &self,
+    url: &str,
+    html: &str,
+    etag: Option<String>,
+    last_modified: Option<String>,
+  ) -> Result<Snapshot, Box<dyn std::error::Error>> {
+    fs::create_dir_all(&self.directory)?;
+
+    let snapshot = Snapshot {
+      url: url.to_string(),
+      sha256: sha256(html),
+      fetched_at: chrono::Utc::now().to_rfc3339(),
+      etag,
+      last_modified,
+    };
+
+    fs::write(self.html_path(&snapshot.sha256), html)?;
+    fs::write(
+      self.metadata_path(&snapshot.sha256),
+      serde_json::to_string_pretty(&snapshot)? + "\n",
+    )?;
+
+    let mut index = self.index()?;
+    index.insert(url.to_string(), snapshot.sha256.clone());
+    fs::write(self.directory.join(INDEX_FILE_NAME), serde_json::to_string_pretty(&index)? + "\n")?;
+
+    debug!("Saved snapshot {} of {}", snapshot.sha256, url);
+    Ok(snapshot)
+  }
+
+  pub fn latest(&self, url: &str) -> Result<Option<Snapshot>, Box<dyn std::error::Error>> {
+    match self.index()?.get(url) {
+      Some(sha256) => self.find(sha256),
+      None => Ok(None),
+    }
+  }
+
+  // The stored snapshot with exactly this content, if there is one
+  pub fn find(&self, sha256: &str) -> Result<Option<Snapshot>, Box<dyn std::error::Error>> {
+    let path = self.metadata_path(sha256);
+    if !path.is_file() {
+      return Ok(None);
+    }
+
+    Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+  }
+
+  pub fn read(&self, snapshot: &Snapshot) -> std::io::Result<String> {
+    fs::read_to_string(self.html_path(&snapshot.sha256))
+  }
+
+  fn index(&self) -> Result<BTreeMap<String, String>, Box<dyn std::error::Error>> {
+    let path = self.directory.join(INDEX_FILE_NAME);
+    if !path.is_file() {
+      return Ok(BTreeMap::new());
+    }
+
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+  }
+
+  fn html_path(&self, sha256: &str) -> PathBuf {
+    self.directory.join(sha256.to_string() + ".html")
+  }
+
+  fn metadata_path(&self, sha256: &str) -> PathBuf {
+    self.directory.join(sha256.to_string() + ".json")
+  }
+}
+
+pub fn sha256(contents: &str) -> String {
+  Sha256::digest(contents.as_bytes())
+    .iter()
+    .map(|byte| format!("{:02x}", byte))
+    .collect()
+}
+
+fn header(response: &reqwest::Response, name: reqwest::header::HeaderName) -> Option<String> {
+  response
+    .headers()
+    .get(name)
+    .and_then(|value| value.to_str().ok())
+    .map(|value| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+  use tokio::net::{TcpListener, TcpStream};
+
+  const PAGE: &str = "<html><body><div class=\"content\">the docs</div></body></html>";
+  const ETAG: &str = "\"v1\"";
+  const LAST_MODIFIED: &str = "Mon, 01 Mar 2021 00:00:00 GMT";
+
+  /*
+   * Stands in for reddit: serves the page with validators, unless the request sends the ETag back, in which case it's
+   * not modified. Returns the head of each request it answered.
+   */
+  async fn serve(listener: TcpListener, requests: usize) -> Vec<String> {
+    let mut heads = Vec::new();
+    for _ in 0..requests {
+      let (mut stream, _) = listener.accept().await.unwrap();
+      let head = read_head(&mut stream).await;
+
+      let response = if head.to_lowercase().contains(&("if-none-match: ".to_string() + ETAG)) {
+        "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n".to_string()
+      } else {
+        format!(
+          "HTTP/1.1 200 OK\r\nETag: {}\r\nLast-Modified: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+          ETAG,
+          LAST_MODIFIED,
+          PAGE.len(),
+          PAGE
+        )
+      };
+      stream.write_all(response.as_bytes()).await.unwrap();
+      heads.push(head);
+    }
+
+    heads
+  }
+
+  async fn read_head(stream: &mut TcpStream) -> String {
+    let mut head = Vec::new();
+    let mut buffer = [0; 1024];
+    while !head.ends_with(b"\r\n\r\n") {
+      let read = stream.read(&mut buffer).await.unwrap();
+      assert!(read > 0, "the connection closed before the end of the request");
+      head.extend_from_slice(&buffer[..read]);
+    }
+
+    String::from_utf8(head).unwrap()
+  }
+
+  fn scratch_directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("reddit_api_scraper_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    directory
+  }
+
+  fn stored_files(directory: &Path) -> Vec<(PathBuf, String)> {
+    let mut files: Vec<_> = fs::read_dir(directory)
+      .unwrap()
+      .map(|entry| {
+        let path = entry.unwrap().path();
+        let contents = fs::read_to_string(&path).unwrap();
+        (path, contents)
+      })
+      .collect();
+    files.sort();
+    files
+  }
+
+  #[tokio::test]
+  async fn refetching_an_unchanged_page_uses_the_stored_snapshot() {
+    let directory = scratch_directory("refetch");
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/dev/api", listener.local_addr().unwrap());
+    let server = tokio::spawn(serve(listener, 2));
+    let store = SnapshotStore::new(&directory);
+
+    let (first, html) = store.fetch(&url).await.unwrap();
+    assert_eq!(html, PAGE);
+    assert_eq!(first.sha256, sha256(PAGE));
+    assert_eq!(first.etag.as_deref(), Some(ETAG));
+    assert_eq!(first.last_modified.as_deref(), Some(LAST_MODIFIED));
+    assert_eq!(store.latest(&url).unwrap(), Some(first.clone()));
+    let stored = stored_files(&directory);
+    // The page, its metadata and the index
+    assert_eq!(stored.len(), 3);
+
+    let (second, html) = store.fetch(&url).await.unwrap();
+    assert_eq!(second, first);
+    assert_eq!(html, PAGE);
+    assert_eq!(stored_files(&directory), stored);
+
+    let heads = server.await.unwrap();
+    let conditional = heads[1].to_lowercase();
+    assert!(!heads[0].to_lowercase().contains("if-none-match"));
+    assert!(conditional.contains(&("if-none-match: ".to_string() + ETAG)));
+    assert!(conditional.contains(&("if-modified-since: ".to_string() + &LAST_MODIFIED.to_lowercase())));
+
+    fs::remove_dir_all(&directory).unwrap();
+  }
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_URL: &str = "https://www.reddit.com/dev/api";
+
+/*
+ * Where the /dev/api HTML comes from. Fetching from reddit is just one option; a saved snapshot (or a directory of
+ * them) or stdin makes generation reproducible and usable without network access.
+ */
+#[derive(Debug)]
+pub enum DocumentSource {
+  Url(String),
+  File(PathBuf),
+  Directory(PathBuf),
+  Stdin,
+}
+
+impl DocumentSource {
+  pub fn from(string: &str) -> DocumentSource {
+    if string == "-" {
+      return DocumentSource::Stdin;
+    }
+
+    if string.starts_with("http://") || string.starts_with("https://") {
+      return DocumentSource::Url(string.to_string());
+    }
+
+    let path = PathBuf::from(string);
+    if path.is_dir() {
+      DocumentSource::Directory(path)
+    } else {
+      DocumentSource::File(path)
+    }
+  }
+
+  pub async fn read(&self) -> Result<String, Box<dyn std::error::Error>> {
+    match self {
+      DocumentSource::Url(url) => Ok(reqwest::get(url).await?.error_for_status()?.text().await?),
+      DocumentSource::File(path) => Ok(fs::read_to_string(path)?),
+      DocumentSource::Directory(directory) => Ok(fs::read_to_string(latest_snapshot(directory)?)?),
+      DocumentSource::Stdin => {
+        let mut html = String::new();
+        std::io::stdin().read_to_string(&mut html)?;
+        Ok(html)
+      }
+    }
+  }
+}
+
+/*
+ * Snapshots are expected to be named so that they sort chronologically (e.g. 2021-03-01.html), so the "latest" one is
+ * simply the last HTML file by name.
+ */
+pub fn latest_snapshot(directory: &Path) -> std::io::Result<PathBuf> {
+  snapshots_in(directory)?.pop().ok_or_else(|| {
+    std::io::Error::new(
+      std::io::ErrorKind::NotFound,
+      format!("No .html snapshots found in {}", directory.display()),
+    )
+  })
+}
+
+pub fn snapshots_in(directory: &Path) -> std::io::Result<Vec<PathBuf>> {
+  let mut snapshots = Vec::new();
+  for entry in fs::read_dir(directory)? {
+    let path = entry?.path();
+    match path.extension().and_then(|extension| extension.to_str()) {
+      Some("html") | Some("htm") => snapshots.push(path),
+      _ => continue,
+    }
+  }
+
+  snapshots.sort();
+  Ok(snapshots)
+}
+
+impl fmt::Display for DocumentSource {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      DocumentSource::Url(url) => write!(f, "{}", url),
+      DocumentSource::File(path) => write!(f, "{}", path.display()),
+      DocumentSource::Directory(path) => write!(f, "{} (latest snapshot)", path.display()),
+      DocumentSource::Stdin => write!(f, "stdin"),
+    }
+  }
+}
@@ -1,14 +1,283 @@
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
+/*
+ * A URI as documented, e.g. [/r/{{subreddit}}]/about/{{location}}. Parameters are written {{name}} and optional parts
+ * are in [brackets]; optional parts can appear anywhere, any number of times, and be nested.
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Segment {
+  Literal { text: String },
+  Parameter { name: String },
+  Optional { segments: Vec<Segment> },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UriPrototype {
+  pub segments: Vec<Segment>,
+}
+
+// One concrete URI an endpoint can be called through: no optional parts, just literals and parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemplateUri {
-  pub template: String,
-  pub parameters: HashMap<String, String>,
-  pub request_fields: HashMap<String, String>,  
+  pub segments: Vec<Segment>,
+}
+
+impl UriPrototype {
+  pub fn parse(prototype: &str) -> Result<UriPrototype, String> {
+    // The segments of each group still open, the whole URI at the bottom
+    let mut groups: Vec<Vec<Segment>> = vec![Vec::new()];
+    let mut literal = String::new();
+
+    let mut rest = prototype;
+    while let Some(c) = rest.chars().next() {
+      if rest.starts_with("{{") {
+        let end = rest
+          .find("}}")
+          .ok_or_else(|| format!("Unmatched {{{{ in '{}'", prototype))?;
+        let name = rest[2..end].trim();
+        if name.is_empty() || name.contains(['{', '}']) {
+          return Err(format!("Malformed parameter '{}' in '{}'", &rest[..end + 2], prototype));
+        }
+
+        end_literal(&mut groups, &mut literal);
+        groups
+          .last_mut()
+          .unwrap()
+          .push(Segment::Parameter { name: name.to_string() });
+        rest = &rest[end + 2..];
+        continue;
+      }
+
+      if rest.starts_with("}}") {
+        return Err(format!("Unmatched }}}} in '{}'", prototype));
+      }
+
+      match c {
+        '[' => {
+          end_literal(&mut groups, &mut literal);
+          groups.push(Vec::new());
+        }
+        ']' => {
+          end_literal(&mut groups, &mut literal);
+          if groups.len() == 1 {
+            return Err(format!("Unmatched ] in '{}'", prototype));
+          }
+          let segments = groups.pop().unwrap();
+          groups.last_mut().unwrap().push(Segment::Optional { segments });
+        }
+        // A lone brace would end up in the template the generated code renders, and fail there
+        '{' | '}' => return Err(format!("Unmatched {} in '{}'", c, prototype)),
+        _ => literal.push(c),
+      }
+      rest = &rest[c.len_utf8()..];
+    }
+
+    end_literal(&mut groups, &mut literal);
+    if groups.len() > 1 {
+      return Err(format!("Unmatched [ in '{}'", prototype));
+    }
+
+    Ok(UriPrototype {
+      segments: groups.pop().unwrap(),
+    })
+  }
+
+  /*
+   * Every combination of the optional parts being left out or put in, e.g. [/r/{{subreddit}}]/about/{{location}} is
+   * both /about/{{location}} and /r/{{subreddit}}/about/{{location}}. Leaving a part out comes first.
+   */
+  pub fn expand(&self) -> Vec<Vec<Segment>> {
+    let mut expanded = Vec::new();
+    for segments in expand(&self.segments) {
+      let segments = merge_literals(segments);
+      if !expanded.contains(&segments) {
+        expanded.push(segments);
+      }
+    }
+
+    expanded
+  }
+}
+
+fn end_literal(groups: &mut [Vec<Segment>], literal: &mut String) {
+  if !literal.is_empty() {
+    groups.last_mut().unwrap().push(Segment::Literal {
+      text: literal.to_string(),
+    });
+    literal.clear();
+  }
+}
+
+fn expand(segments: &[Segment]) -> Vec<Vec<Segment>> {
+  let mut expanded = vec![Vec::new()];
+  for segment in segments {
+    match segment {
+      Segment::Optional { segments } => {
+        let optional = expand(segments);
+        let mut with_optional = Vec::new();
+        for prefix in expanded {
+          with_optional.push(prefix.clone());
+          for variant in &optional {
+            let mut segments = prefix.clone();
+            segments.extend(variant.iter().cloned());
+            with_optional.push(segments);
+          }
+        }
+        expanded = with_optional;
+      }
+      _ => {
+        for prefix in &mut expanded {
+          prefix.push(segment.clone());
+        }
+      }
+    }
+  }
+
+  expanded
+}
+
+// Optional parts leave literals next to each other, e.g. [/r/{{subreddit}}]/about with /r/ and /about
+fn merge_literals(segments: Vec<Segment>) -> Vec<Segment> {
+  let mut merged: Vec<Segment> = Vec::new();
+  for segment in segments {
+    match (merged.last_mut(), segment) {
+      (Some(Segment::Literal { text }), Segment::Literal { text: next }) => text.push_str(&next),
+      (_, segment) => merged.push(segment),
+    }
+  }
+
+  merged
+}
+
+impl TemplateUri {
+  // E.g. /r/{{subreddit}}/about/{{location}}, which is how the generated code renders the URI
+  pub fn template(&self) -> String {
+    render(&self.segments)
+  }
+
+  // The names of the parameters in the URI, in order
+  pub fn parameters(&self) -> Vec<&str> {
+    self
+      .segments
+      .iter()
+      .filter_map(|segment| match segment {
+        Segment::Parameter { name } => Some(name.as_str()),
+        _ => None,
+      })
+      .collect()
+  }
+}
+
+fn render(segments: &[Segment]) -> String {
+  let mut rendered = String::new();
+  for segment in segments {
+    match segment {
+      Segment::Literal { text } => rendered.push_str(text),
+      Segment::Parameter { name } => rendered.push_str(&("{{".to_string() + name + "}}")),
+      Segment::Optional { segments } => rendered.push_str(&("[".to_string() + &render(segments) + "]")),
+    }
+  }
+
+  rendered
+}
+
+impl fmt::Display for UriPrototype {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", render(&self.segments))
+  }
 }
 
 impl fmt::Display for TemplateUri {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    write!(f, "{}, {:?}", self.template, self.parameters)
+    write!(f, "{}, {:?}", self.template(), self.parameters())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn templates(prototype: &str) -> Vec<String> {
+    UriPrototype::parse(prototype)
+      .unwrap()
+      .expand()
+      .into_iter()
+      .map(|segments| TemplateUri { segments }.template())
+      .collect()
+  }
+
+  fn literal(text: &str) -> Segment {
+    Segment::Literal { text: text.to_string() }
+  }
+
+  fn parameter(name: &str) -> Segment {
+    Segment::Parameter { name: name.to_string() }
+  }
+
+  #[test]
+  fn parses_literals_parameters_and_optional_parts() {
+    assert_eq!(
+      UriPrototype::parse("[/r/{{subreddit}}]/about/{{location}}").unwrap().segments,
+      [
+        Segment::Optional {
+          segments: vec![literal("/r/"), parameter("subreddit")]
+        },
+        literal("/about/"),
+        parameter("location"),
+      ]
+    );
+  }
+
+  #[test]
+  fn expands_without_optional_parts() {
+    assert_eq!(templates("/api/v1/me"), ["/api/v1/me"]);
+  }
+
+  #[test]
+  fn expands_each_optional_part_left_out_first() {
+    assert_eq!(
+      templates("[/r/{{subreddit}}]/about/{{location}}"),
+      ["/about/{{location}}", "/r/{{subreddit}}/about/{{location}}"]
+    );
+  }
+
+  #[test]
+  fn expands_every_combination_of_several_optional_parts() {
+    assert_eq!(
+      templates("[/r/{{subreddit}}]/comments/{{article}}[/{{slug}}]"),
+      [
+        "/comments/{{article}}",
+        "/comments/{{article}}/{{slug}}",
+        "/r/{{subreddit}}/comments/{{article}}",
+        "/r/{{subreddit}}/comments/{{article}}/{{slug}}",
+      ]
+    );
+  }
+
+  #[test]
+  fn expands_nested_optional_parts() {
+    assert_eq!(
+      templates("/api/multi[/{{multipath}}[/r/{{srname}}]]"),
+      ["/api/multi", "/api/multi/{{multipath}}", "/api/multi/{{multipath}}/r/{{srname}}"]
+    );
+  }
+
+  #[test]
+  fn rejects_unmatched_brackets_and_braces() {
+    for (prototype, message) in [
+      ("[/r/{{subreddit}}/about", "Unmatched ["),
+      ("/r/{{subreddit}}]/about", "Unmatched ]"),
+      ("/r/{{subreddit/about", "Unmatched {{"),
+      ("/r/subreddit}}/about", "Unmatched }}"),
+      ("/x/{{a}}}", "Unmatched }"),
+      ("/x/{a}", "Unmatched {"),
+      ("/x/{{}}", "Malformed parameter"),
+      ("/x/{{{a}}", "Malformed parameter"),
+    ] {
+      let error = UriPrototype::parse(prototype).unwrap_err();
+      assert!(error.starts_with(message), "'{}' gave '{}'", prototype, error);
+    }
   }
 }
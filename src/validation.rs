@@ -0,0 +1,317 @@
+use crate::catalog::{ApiCatalog, Location};
+use crate::config;
+use crate::generator;
+use crate::scrape_error::ScrapeError;
+
+use serde::Serialize;
+use std::fmt;
+
+// Anything in the docs that won't make it into the bindings as documented
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Issue {
+  // The entry couldn't be scraped at all
+  NotScraped { failure: ScrapeError },
+  // A documented URI that couldn't be parsed, e.g. because of an unmatched brace
+  MalformedUri { anchor: String, uri: String, reason: String },
+  // Nothing is generated for the endpoint's verb
+  Skipped { anchor: String, verb: String },
+  NoUris { anchor: String },
+  // A parameter in the URI that the endpoint's parameter table doesn't mention
+  UndocumentedUriParameter { anchor: String, uri: String, parameter: String },
+  // A parameter name that isn't a Rust identifier and isn't renamed or skipped in the config
+  UnmappableParameter { anchor: String, parameter: String },
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+  pub issues: Vec<Issue>,
+}
+
+impl Report {
+  pub fn is_empty(&self) -> bool {
+    self.issues.is_empty()
+  }
+}
+
+pub fn validate(catalog: &ApiCatalog, fields: &config::Fields) -> Report {
+  let mut issues = Vec::new();
+
+  for failure in &catalog.failures {
+    issues.push(match failure {
+      ScrapeError::MalformedUri { anchor, uri, reason, .. } => Issue::MalformedUri {
+        anchor: anchor.clone(),
+        uri: uri.clone(),
+        reason: reason.clone(),
+      },
+      failure => Issue::NotScraped {
+        failure: failure.clone(),
+      },
+    });
+  }
+
+  for (_, endpoint) in catalog.endpoints() {
+    let anchor = &endpoint.anchor;
+    if !generator::supports(&endpoint.verb) {
+      issues.push(Issue::Skipped {
+        anchor: anchor.clone(),
+        verb: endpoint.verb.to_string(),
+      });
+      continue;
+    }
+
+    if endpoint.uris.is_empty() {
+      issues.push(Issue::NoUris { anchor: anchor.clone() });
+    }
+
+    // Once for each parameter, even though a parameter in an optional part is in several of the URIs
+    let mut undocumented = Vec::new();
+    for uri in &endpoint.uris {
+      for parameter in uri.parameters() {
+        if endpoint.parameter(parameter).is_none() && !undocumented.contains(&parameter) {
+          undocumented.push(parameter);
+          issues.push(Issue::UndocumentedUriParameter {
+            anchor: anchor.clone(),
+            uri: uri.template(),
+            parameter: parameter.to_string(),
+          });
+        }
+      }
+    }
+
+    // URI parameters are filled in to the URI by name rather than becoming fields, so any name will do
+    for parameter in endpoint.parameters.iter().filter(|parameter| parameter.location != Location::Uri) {
+      let name = &parameter.name;
+      if !is_identifier(name) && !fields.skip.contains(name) && !fields.rename.contains_key(name) {
+        issues.push(Issue::UnmappableParameter {
+          anchor: anchor.clone(),
+          parameter: name.clone(),
+        });
+      }
+    }
+  }
+
+  Report { issues }
+}
+
+const KEYWORDS: [&str; 51] = [
+  "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let",
+  "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+  "true", "type", "unsafe", "use", "where", "while", "async", "await", "dyn", "abstract", "become", "box", "do", "final",
+  "macro", "override", "priv", "typeof", "unsized", "virtual", "yield", "try",
+];
+
+// Whether the name can be used as a field as it is, as the generator does with any parameter that isn't renamed
+fn is_identifier(name: &str) -> bool {
+  let mut chars = name.chars();
+  let starts_well = match chars.next() {
+    Some(first) => first == '_' || first.is_ascii_alphabetic(),
+    None => false,
+  };
+
+  starts_well && chars.all(|c| c == '_' || c.is_ascii_alphanumeric()) && name != "_" && !KEYWORDS.contains(&name)
+}
+
+impl fmt::Display for Issue {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Issue::NotScraped { failure } => write!(f, "not scraped: {}", failure),
+      Issue::MalformedUri { anchor, reason, .. } => write!(f, "{}: malformed URI: {}", anchor, reason),
+      Issue::Skipped { anchor, verb } => write!(f, "{}: skipped, nothing is generated for {} yet", anchor, verb),
+      Issue::NoUris { anchor } => write!(f, "{}: no URIs", anchor),
+      Issue::UndocumentedUriParameter { anchor, uri, parameter } => {
+        write!(f, "{}: {} isn't in the parameter table, but is in {}", anchor, parameter, uri)
+      }
+      Issue::UnmappableParameter { anchor, parameter } => write!(
+        f,
+        "{}: '{}' isn't a Rust identifier, rename or skip it under [fields] in the config",
+        anchor, parameter
+      ),
+    }
+  }
+}
+
+impl fmt::Display for Report {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for issue in &self.issues {
+      writeln!(f, "{}", issue)?;
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::catalog::{ApiSection, Endpoint, Parameter, ParameterType};
+  use crate::http_verb::HttpVerb;
+  use crate::template_uri::{TemplateUri, UriPrototype};
+  use std::collections::BTreeMap;
+
+  // Optional parts of the prototypes are expanded, like they are when scraping
+  fn endpoint(anchor: &str, verb: HttpVerb, prototypes: &[&str], parameters: &[(&str, Location)]) -> Endpoint {
+    Endpoint {
+      anchor: anchor.to_string(),
+      verb,
+      uris: prototypes
+        .iter()
+        .flat_map(|prototype| UriPrototype::parse(prototype).unwrap().expand())
+        .map(|segments| TemplateUri { segments })
+        .collect(),
+      parameters: parameters
+        .iter()
+        .map(|(name, location)| Parameter {
+          name: name.to_string(),
+          location: *location,
+          description: String::new(),
+          parameter_type: ParameterType::String,
+        })
+        .collect(),
+      description: String::new(),
+      scopes: Vec::new(),
+      listing: false,
+    }
+  }
+
+  fn validate_endpoints(endpoints: Vec<Endpoint>, failures: Vec<ScrapeError>) -> Vec<Issue> {
+    let catalog = ApiCatalog {
+      sections: vec![ApiSection {
+        name: "misc".to_string(),
+        endpoints,
+      }],
+      failures,
+      ..ApiCatalog::default()
+    };
+    let mut rename = BTreeMap::new();
+    rename.insert("type".to_string(), "r#type".to_string());
+    rename.insert("g-recaptcha-response".to_string(), "g_recaptcha_response".to_string());
+    let fields = config::Fields {
+      skip: vec!["uh / X-Modhash header".to_string()],
+      rename,
+    };
+
+    validate(&catalog, &fields).issues
+  }
+
+  #[test]
+  fn nothing_to_report() {
+    let me = endpoint("GET_api_v1_me", HttpVerb::GET, &["/api/v1/me"], &[("raw_json", Location::Query)]);
+    assert_eq!(validate_endpoints(vec![me], Vec::new()), []);
+  }
+
+  #[test]
+  fn failures_are_not_scraped() {
+    let failure = ScrapeError::UnknownVerb {
+      section: "misc".to_string(),
+      anchor: "api_v1_me".to_string(),
+      verb: "api".to_string(),
+    };
+    assert_eq!(
+      validate_endpoints(Vec::new(), vec![failure.clone()]),
+      [Issue::NotScraped { failure }]
+    );
+  }
+
+  #[test]
+  fn uris_that_cant_be_parsed_are_malformed() {
+    let failure = ScrapeError::MalformedUri {
+      section: "wiki".to_string(),
+      anchor: "GET_wiki_{page}".to_string(),
+      uri: "/wiki/{page}".to_string(),
+      reason: "Unmatched { in '/wiki/{page}'".to_string(),
+    };
+    assert_eq!(
+      validate_endpoints(Vec::new(), vec![failure]),
+      [Issue::MalformedUri {
+        anchor: "GET_wiki_{page}".to_string(),
+        uri: "/wiki/{page}".to_string(),
+        reason: "Unmatched { in '/wiki/{page}'".to_string(),
+      }]
+    );
+  }
+
+  #[test]
+  fn verbs_nothing_is_generated_for_are_skipped() {
+    let delete = endpoint(
+      "DELETE_api_v1_me_friends_{username}",
+      HttpVerb::DELETE,
+      &["/api/v1/me/friends/{{username}}"],
+      &[("username", Location::Uri), ("()", Location::Query)],
+    );
+    assert_eq!(
+      validate_endpoints(vec![delete], Vec::new()),
+      [Issue::Skipped {
+        anchor: "DELETE_api_v1_me_friends_{username}".to_string(),
+        verb: "DELETE".to_string(),
+      }]
+    );
+  }
+
+  #[test]
+  fn endpoints_without_uris() {
+    let bare = endpoint("GET_api_v1_me", HttpVerb::GET, &[], &[]);
+    assert_eq!(
+      validate_endpoints(vec![bare], Vec::new()),
+      [Issue::NoUris {
+        anchor: "GET_api_v1_me".to_string()
+      }]
+    );
+  }
+
+  #[test]
+  fn undocumented_uri_parameters_are_reported_once_each() {
+    let about = endpoint(
+      "GET_r_{subreddit}_about_{where}",
+      HttpVerb::GET,
+      &["[/r/{{subreddit}}]/about/{{where}}"],
+      &[],
+    );
+    assert_eq!(
+      validate_endpoints(vec![about], Vec::new()),
+      [
+        Issue::UndocumentedUriParameter {
+          anchor: "GET_r_{subreddit}_about_{where}".to_string(),
+          uri: "/about/{{where}}".to_string(),
+          parameter: "where".to_string(),
+        },
+        Issue::UndocumentedUriParameter {
+          anchor: "GET_r_{subreddit}_about_{where}".to_string(),
+          uri: "/r/{{subreddit}}/about/{{where}}".to_string(),
+          parameter: "subreddit".to_string(),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn parameters_that_arent_identifiers_are_unmappable_unless_renamed_or_skipped() {
+    let submit = endpoint(
+      "POST_api_submit",
+      HttpVerb::POST,
+      &["/api/{{api-path}}"],
+      &[
+        ("api-path", Location::Uri),
+        ("where", Location::Body),
+        ("type", Location::Body),
+        ("g-recaptcha-response", Location::Body),
+        ("uh / X-Modhash header", Location::Body),
+        ("sr-name", Location::Body),
+        ("kind", Location::Body),
+      ],
+    );
+    assert_eq!(
+      validate_endpoints(vec![submit], Vec::new()),
+      [
+        Issue::UnmappableParameter {
+          anchor: "POST_api_submit".to_string(),
+          parameter: "where".to_string(),
+        },
+        Issue::UnmappableParameter {
+          anchor: "POST_api_submit".to_string(),
+          parameter: "sr-name".to_string(),
+        },
+      ]
+    );
+  }
+}
@@ -0,0 +1,37 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/*
+ * Progress output goes to stderr so that stdout stays clean for command output (e.g. `diff --format json` or `history`
+ * being piped elsewhere). The macros are only for this crate, so they don't clash with the `log` or `tracing` macros
+ * of a crate embedding it; the binary has its own.
+ */
+pub const QUIET: u8 = 0;
+pub const NORMAL: u8 = 1;
+pub const VERBOSE: u8 = 2;
+
+// The library says nothing unless asked to, the binary sets the level from its arguments
+static LEVEL: AtomicU8 = AtomicU8::new(QUIET);
+
+pub fn set(level: u8) {
+  LEVEL.store(level, Ordering::Relaxed);
+}
+
+pub fn at_least(level: u8) -> bool {
+  LEVEL.load(Ordering::Relaxed) >= level
+}
+
+macro_rules! info {
+  ($($arg:tt)*) => {
+    if $crate::verbosity::at_least($crate::verbosity::NORMAL) {
+      eprintln!($($arg)*);
+    }
+  };
+}
+
+macro_rules! debug {
+  ($($arg:tt)*) => {
+    if $crate::verbosity::at_least($crate::verbosity::VERBOSE) {
+      eprintln!($($arg)*);
+    }
+  };
+}
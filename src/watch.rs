@@ -0,0 +1,25 @@
+use reddit_api_scraper::diff::CatalogDiff;
+
+use serde::Serialize;
+
+// What gets POSTed to the webhook when a change in the docs changes the documented endpoints
+#[derive(Debug, Serialize)]
+pub struct ChangeNotification<'a> {
+  pub source: &'a str,
+  pub previous_sha256: &'a str,
+  pub sha256: &'a str,
+  pub output_dir: String,
+  pub diff: &'a CatalogDiff,
+}
+
+pub async fn notify(webhook: &str, notification: &ChangeNotification<'_>) -> Result<(), Box<dyn std::error::Error>> {
+  reqwest::Client::new()
+    .post(webhook)
+    .json(notification)
+    .send()
+    .await?
+    .error_for_status()?;
+
+  info!("Notified {}", webhook);
+  Ok(())
+}